@@ -0,0 +1,123 @@
+use crate::save::read;
+use godot::prelude::*;
+use std::collections::HashMap;
+
+pub const MAX_TIER: i64 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Ability {
+    Dash,
+    Slide,
+    DashAttack,
+    AuraAttack,
+    FallAttack,
+    Climb,
+}
+
+impl Ability {
+    pub const ALL: [Ability; 6] = [
+        Ability::Dash,
+        Ability::Slide,
+        Ability::DashAttack,
+        Ability::AuraAttack,
+        Ability::FallAttack,
+        Ability::Climb,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Ability::Dash => "dash",
+            Ability::Slide => "slide",
+            Ability::DashAttack => "dash_attack",
+            Ability::AuraAttack => "aura_attack",
+            Ability::FallAttack => "fall_attack",
+            Ability::Climb => "climb",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Ability> {
+        Ability::ALL
+            .into_iter()
+            .find(|ability| ability.name() == name)
+    }
+}
+
+#[derive(Default)]
+pub struct Abilities {
+    locked: Vec<Ability>,
+    tiers: HashMap<Ability, i64>,
+}
+
+impl Abilities {
+    pub fn unlocked(&self, ability: Ability) -> bool {
+        !self.locked.contains(&ability)
+    }
+
+    pub fn unlock(&mut self, ability: Ability) -> bool {
+        let locked = !self.unlocked(ability);
+
+        self.locked.retain(|other| *other != ability);
+
+        locked
+    }
+
+    pub fn lock(&mut self, ability: Ability) -> bool {
+        if !self.unlocked(ability) {
+            return false;
+        }
+
+        self.locked.push(ability);
+
+        true
+    }
+
+    pub fn tier(&self, ability: Ability) -> i64 {
+        self.tiers.get(&ability).copied().unwrap_or(0)
+    }
+
+    pub fn upgrade(&mut self, ability: Ability) -> i64 {
+        let tier = (self.tier(ability) + 1).min(MAX_TIER);
+
+        self.tiers.insert(ability, tier);
+
+        tier
+    }
+
+    pub fn save(&self) -> Dictionary {
+        let mut tiers = Dictionary::new();
+
+        for (ability, tier) in &self.tiers {
+            tiers.set(ability.name(), *tier);
+        }
+
+        dict! {
+            "locked": self
+                .locked
+                .iter()
+                .map(|ability| GString::from(ability.name()))
+                .collect::<Array<GString>>(),
+            "tiers": tiers,
+        }
+    }
+
+    pub fn restore(&mut self, data: &Dictionary) {
+        self.locked = read::<VariantArray>(data, "locked")
+            .unwrap_or_default()
+            .iter_shared()
+            .filter_map(|name| name.try_to::<GString>().ok())
+            .filter_map(|name| Ability::from_name(&name.to_string()))
+            .collect();
+
+        let tiers = read::<Dictionary>(data, "tiers").unwrap_or_default();
+
+        self.tiers = Ability::ALL
+            .into_iter()
+            .filter_map(|ability| {
+                // JSON numbers always come back as floats.
+                let tier = read::<f64>(&tiers, ability.name())? as i64;
+
+                Some((ability, tier.clamp(0, MAX_TIER)))
+            })
+            .collect();
+    }
+}
@@ -1,32 +1,104 @@
 use crate::player::Player;
 use godot::{
-    classes::{Area2D, IArea2D},
+    classes::{Area2D, CollisionShape2D, IArea2D},
     prelude::*,
 };
 
+#[derive(GodotConvert, Var, Export, Clone, Copy, Default, PartialEq, Debug)]
+#[godot(via = i64)]
+pub enum ClimbKind {
+    #[default]
+    Ladder,
+    Vine,
+    Rope,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClimbSpot {
+    pub area: InstanceId,
+    pub shape: i32,
+    pub body_shape: i32,
+    pub centre: f32,
+    pub top: f32,
+    pub kind: ClimbKind,
+    pub sway_amplitude: f32,
+    pub sway_speed: f32,
+}
+
 #[derive(GodotClass)]
 #[class(init, base=Area2D)]
 struct ClimbableArea {
+    #[export]
+    kind: ClimbKind,
+    #[export]
+    #[init(val = 12.)]
+    sway_amplitude: f32,
+    #[export]
+    #[init(val = 2.)]
+    sway_speed: f32,
+
     base: Base<Area2D>,
 }
 
 #[godot_api]
 impl ClimbableArea {
+    fn spot(&self, body_shape: i32, shape: i32) -> Option<ClimbSpot> {
+        let owner = self.base().shape_find_owner(shape);
+        let collision = self
+            .base()
+            .shape_owner_get_owner(owner)?
+            .try_cast::<CollisionShape2D>()
+            .ok()?;
+
+        let rect = collision.get_shape()?.get_rect();
+        let position = collision.get_global_position();
+        let scale = collision.get_global_scale();
+
+        Some(ClimbSpot {
+            area: self.base().instance_id(),
+            shape,
+            body_shape,
+            centre: position.x + (rect.position.x + rect.size.x / 2.) * scale.x,
+            top: position.y + rect.position.y * scale.y,
+            kind: self.kind,
+            sway_amplitude: self.sway_amplitude,
+            sway_speed: self.sway_speed,
+        })
+    }
+
     #[func]
-    fn on_body_entered(&mut self, body: Gd<Node2D>) {
+    fn on_body_shape_entered(
+        &mut self,
+        _body_rid: Rid,
+        body: Gd<Node2D>,
+        body_shape_index: i32,
+        local_shape_index: i32,
+    ) {
         let Ok(mut player) = body.try_cast::<Player>() else {
             return;
         };
 
-        player.bind_mut().set_climbable(true);
+        if let Some(spot) = self.spot(body_shape_index, local_shape_index) {
+            player.bind_mut().enter_climbable(spot);
+        }
     }
 
     #[func]
-    fn on_body_exited(&mut self, body: Gd<Node2D>) {
+    fn on_body_shape_exited(
+        &mut self,
+        _body_rid: Rid,
+        body: Gd<Node2D>,
+        body_shape_index: i32,
+        local_shape_index: i32,
+    ) {
         let Ok(mut player) = body.try_cast::<Player>() else {
             return;
         };
 
-        player.bind_mut().set_climbable(false);
+        player.bind_mut().exit_climbable(
+            self.base().instance_id(),
+            body_shape_index,
+            local_shape_index,
+        );
     }
 }
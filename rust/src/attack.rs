@@ -0,0 +1,83 @@
+use crate::status::StatusEffect;
+use godot::prelude::*;
+
+pub struct Attack {
+    pub damage: f32,
+    pub resisted_damage: f32,
+    // Horizontal speed points away from the attacker.
+    pub knockback: Vector2,
+    pub hitstun: f64,
+    pub effects: &'static [StatusEffect],
+}
+
+impl Attack {
+    pub fn damage(&self, resistance: bool) -> f32 {
+        if resistance {
+            self.resisted_damage
+        } else {
+            self.damage
+        }
+    }
+
+    pub fn impulse(&self, attacker: Vector2, target: Vector2) -> Vector2 {
+        let direction = if target.x < attacker.x { -1. } else { 1. };
+
+        Vector2::new(self.knockback.x * direction, self.knockback.y)
+    }
+}
+
+pub const BASIC_ATTACK: Attack = Attack {
+    damage: 15.,
+    resisted_damage: 7.5,
+    knockback: Vector2::new(0., -400.),
+    hitstun: 0.3,
+    effects: &[],
+};
+
+pub const STRONG_ATTACK: Attack = Attack {
+    damage: 35.,
+    resisted_damage: 25.,
+    knockback: Vector2::new(0., 400.),
+    hitstun: 0.4,
+    effects: &[StatusEffect::bleed(4., 2.)],
+};
+
+pub const FALL_ATTACK: Attack = Attack {
+    damage: 35.,
+    resisted_damage: 25.,
+    knockback: Vector2::new(0., 400.),
+    hitstun: 0.4,
+    effects: &[],
+};
+
+pub const EARTHQUAKE: Attack = Attack {
+    damage: 50.,
+    resisted_damage: 30.,
+    knockback: Vector2::new(1000., -1500.),
+    hitstun: 0.8,
+    effects: &[StatusEffect::stun(0.8)],
+};
+
+pub const SWORD_AURA: Attack = Attack {
+    damage: 40.,
+    resisted_damage: 20.,
+    knockback: Vector2::new(0., 0.),
+    hitstun: 0.3,
+    effects: &[StatusEffect::burn(3., 4.)],
+};
+
+pub const ENEMY_ATTACK1: Attack = Attack {
+    damage: 15.,
+    resisted_damage: 15.,
+    knockback: Vector2::new(200., -400.),
+    hitstun: 0.3,
+    effects: &[StatusEffect::bleed(3., 2.)],
+};
+
+pub const ENEMY_ATTACK2: Attack = Attack {
+    damage: 35.,
+    resisted_damage: 35.,
+    knockback: Vector2::new(400., 800.),
+    hitstun: 0.5,
+    effects: &[StatusEffect::slow(2., 0.5), StatusEffect::weaken(3., 0.8)],
+};
@@ -0,0 +1,193 @@
+use crate::{enemy::Enemy, game::game_state};
+use godot::{
+    classes::{AudioServer, AudioStream, AudioStreamPlayer, AudioStreamPlayer2D, INode},
+    global::{linear_to_db, move_toward},
+    prelude::*,
+};
+
+const SILENT_DB: f64 = -60.;
+
+pub const BUSES: [&str; 3] = ["Master", "Music", "SFX"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sfx {
+    Footstep,
+    Swing,
+    Hit,
+    Land,
+    Aggro,
+}
+
+// Owns the SFX voices and the music layers. Gameplay code only sends events
+// through `play_sfx`.
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+pub struct AudioManager {
+    #[export]
+    footstep: Option<Gd<AudioStream>>,
+    #[export]
+    swing: Option<Gd<AudioStream>>,
+    #[export]
+    hit: Option<Gd<AudioStream>>,
+    #[export]
+    land: Option<Gd<AudioStream>>,
+    #[export]
+    aggro: Option<Gd<AudioStream>>,
+    #[export]
+    #[init(val = 8)]
+    max_voices: i64,
+    // Voices one sound may hold at once before new plays are dropped.
+    #[export]
+    #[init(val = 3)]
+    max_per_sound: i64,
+    // Seconds for the combat layer to fade fully in or out.
+    #[export]
+    #[init(val = 1.5)]
+    music_fade: f64,
+    #[export]
+    combat_volume_db: f64,
+
+    voices: Vec<Gd<AudioStreamPlayer2D>>,
+    combat_level: f64,
+
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl AudioManager {
+    // Sets a bus to a linear volume from GameState, which owns the values.
+    pub fn apply_volume(&self, bus: &str, volume: f32) {
+        let mut server = AudioServer::singleton();
+        let index = server.get_bus_index(bus);
+
+        if index < 0 {
+            godot_warn!("Unknown audio bus {bus}.");
+            return;
+        }
+
+        server.set_bus_volume_db(index, linear_to_db(volume as f64) as f32);
+        server.set_bus_mute(index, volume <= 0.);
+    }
+
+    pub fn play(&mut self, sfx: Sfx, position: Vector2) {
+        let stream = match sfx {
+            Sfx::Footstep => &self.footstep,
+            Sfx::Swing => &self.swing,
+            Sfx::Hit => &self.hit,
+            Sfx::Land => &self.land,
+            Sfx::Aggro => &self.aggro,
+        };
+        let Some(stream) = stream.clone() else {
+            return;
+        };
+
+        let playing = self
+            .voices
+            .iter()
+            .filter(|voice| voice.is_playing() && voice.get_stream().as_ref() == Some(&stream))
+            .count();
+
+        if playing >= self.max_per_sound.max(1) as usize {
+            return;
+        }
+
+        // Steal the voice closest to finishing when all are busy.
+        let voice = self
+            .voices
+            .iter()
+            .find(|voice| !voice.is_playing())
+            .or_else(|| {
+                self.voices
+                    .iter()
+                    .min_by(|a, b| remaining(a).total_cmp(&remaining(b)))
+            });
+        let Some(mut voice) = voice.cloned() else {
+            return;
+        };
+
+        voice.set_stream(&stream);
+        voice.set_global_position(position);
+        voice.play();
+    }
+
+    fn combat(&self) -> bool {
+        self.base().get_tree().is_some_and(|tree| {
+            tree.get_nodes_in_group("enemy")
+                .iter_shared()
+                .filter_map(|enemy| enemy.try_cast::<Enemy>().ok())
+                .any(|enemy| enemy.bind().get_aggro() && enemy.bind().get_hp() > 0.)
+        })
+    }
+}
+
+#[godot_api]
+impl INode for AudioManager {
+    fn ready(&mut self) {
+        self.base_mut().add_to_group("audio");
+
+        if let Some(state) = game_state(self.base().get_tree()) {
+            let state = state.bind();
+
+            for bus in BUSES {
+                self.apply_volume(bus, state.volume(bus.into()));
+            }
+        }
+
+        for _ in 0..self.max_voices.max(1) {
+            let mut voice = AudioStreamPlayer2D::new_alloc();
+
+            voice.set_bus("SFX");
+            self.base_mut().add_child(&voice);
+            self.voices.push(voice);
+        }
+
+        self.base()
+            .get_node_as::<AudioStreamPlayer>("CombatMusic")
+            .set_volume_db(SILENT_DB as f32);
+    }
+
+    fn process(&mut self, delta: f64) {
+        let target = if self.combat() { 1. } else { 0. };
+        let step = delta / self.music_fade.max(f64::EPSILON);
+
+        self.combat_level = move_toward(self.combat_level, target, step);
+
+        let volume_db = if self.combat_level > 0. {
+            self.combat_volume_db + linear_to_db(self.combat_level)
+        } else {
+            SILENT_DB
+        };
+
+        let music = self.base().get_node_as::<AudioStreamPlayer>("Music");
+        let mut combat = self.base().get_node_as::<AudioStreamPlayer>("CombatMusic");
+
+        combat.set_volume_db(volume_db.max(SILENT_DB) as f32);
+
+        // Both layers run all the time so the combat one stays in sync. It is
+        // started here rather than in `ready` so a stream assigned later, or
+        // one that stopped, joins the music where it currently is.
+        if combat.get_stream().is_some() && !combat.is_playing() && music.is_playing() {
+            combat
+                .play_ex()
+                .from_position(music.get_playback_position() as f32)
+                .done();
+        }
+    }
+}
+
+fn remaining(voice: &Gd<AudioStreamPlayer2D>) -> f64 {
+    voice.get_stream().map_or(0., |stream| {
+        stream.get_length() - voice.get_playback_position()
+    })
+}
+
+pub fn audio_manager(tree: Option<Gd<SceneTree>>) -> Option<Gd<AudioManager>> {
+    tree.and_then(|tree| tree.get_first_node_in_group("audio"))
+        .and_then(|audio| audio.try_cast::<AudioManager>().ok())
+}
+
+pub fn play_sfx(tree: Option<Gd<SceneTree>>, sfx: Sfx, position: Vector2) {
+    if let Some(mut audio) = audio_manager(tree) {
+        audio.bind_mut().play(sfx, position);
+    }
+}
@@ -1,6 +1,15 @@
-use crate::enemy::Enemy;
+use crate::{
+    attack::SWORD_AURA,
+    combat::{land, Attacker},
+    effect::TimedEffect,
+    facing::Facing,
+    hitbox::Hitbox,
+    platform::OneWayPlatform,
+    pool::{park, Pooled},
+    save::read,
+};
 use godot::{
-    classes::{GpuParticles2D, Sprite2D, Timer},
+    classes::{CharacterBody2D, PhysicsRayQueryParameters2D},
     obj::WithBaseField,
     prelude::*,
 };
@@ -8,10 +17,43 @@ use godot::{
 #[derive(GodotClass)]
 #[class(init, base=Node2D)]
 pub struct SwordAura {
+    #[export]
+    #[init(val = 600.)]
+    speed: f32,
+    // Distance travelled before the aura starts fading, see its `Effect`.
+    #[export]
+    #[init(val = 900.)]
+    range: f32,
+    #[export]
+    #[init(val = 1.)]
+    width: f32,
+    // Walls either stop the aura where it is or shatter it at once.
+    #[export]
+    #[init(val = 1)]
+    wall_mask: u32,
+    #[export]
     #[init(val = true)]
-    delay: bool,
-    #[var]
-    flipped: bool,
+    shatter_on_wall: bool,
+    #[export]
+    #[init(val = 1. / 30.)]
+    shatter_fade_duration: f64,
+    // Enemies passed through before the next one breaks the aura, negative
+    // for no limit.
+    #[export]
+    #[init(val = 3)]
+    max_pierce: i64,
+    // Share of the damage lost by the time the aura reaches its range.
+    #[export]
+    #[init(val = 0.5)]
+    falloff: f32,
+    attacker: Option<Attacker>,
+    #[init(val = 1.)]
+    reach: f32,
+    travelled: f32,
+    pierced: i64,
+    stopped: bool,
+    shattered: bool,
+    released: bool,
 
     base: Base<Node2D>,
 }
@@ -19,56 +61,161 @@ pub struct SwordAura {
 #[godot_api]
 impl SwordAura {
     #[func]
-    fn on_timeout(&mut self) {
-        self.delay = false;
+    pub fn set_facing(&mut self, direction: i32) {
+        self.facing().bind_mut().set_facing(direction);
+    }
+
+    pub fn set_attacker(&mut self, attacker: Attacker) {
+        self.attacker = Some(attacker);
+    }
+
+    pub fn extend(&mut self, reach: f32) {
+        self.reach = reach;
+    }
+
+    fn max_range(&self) -> f32 {
+        self.range * self.reach
+    }
+
+    fn facing(&self) -> Gd<Facing> {
+        self.base().get_node_as::<Facing>("Facing")
+    }
+
+    fn effect(&self) -> Gd<TimedEffect> {
+        self.base().get_node_as::<TimedEffect>("Effect")
+    }
+
+    fn shatter(&mut self) {
+        self.stopped = true;
+        self.shattered = true;
+        self.effect()
+            .bind_mut()
+            .fade_within(self.shatter_fade_duration);
+    }
+
+    fn hits_wall(&self, step: f32) -> bool {
+        let position = self.base().get_global_position();
+        let Some(mut space) = self
+            .base()
+            .get_world_2d()
+            .and_then(|world| world.get_direct_space_state())
+        else {
+            return false;
+        };
+        let Some(mut query) =
+            PhysicsRayQueryParameters2D::create(position, position + Vector2::new(step, 0.))
+        else {
+            return false;
+        };
+
+        query.set_collision_mask(self.wall_mask);
+
+        read::<Gd<Object>>(&space.intersect_ray(&query), "collider").is_some_and(|collider| {
+            collider.clone().try_cast::<CharacterBody2D>().is_err()
+                && collider.try_cast::<OneWayPlatform>().is_err()
+        })
+    }
+
+    #[func]
+    fn release(&mut self) {
+        Pooled::release(self);
     }
 
     #[func]
     fn on_aura_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Enemy>() else {
+        let Some(mut attacker) = self.attacker else {
             return;
         };
 
-        if !body.bind().get_invincible() {
-            let resistance = body.bind().get_resistance();
-            let hp: f32 = body.bind().get_hp();
+        if self.shattered {
+            return;
+        }
 
-            body.bind_mut()
-                .set_hp(hp - if resistance { 20. } else { 40. });
+        let progress = (self.travelled / self.max_range()).clamp(0., 1.);
 
-            if !resistance {
-                body.bind_mut().set_hit(true);
-            }
+        attacker.position = self.base().get_position();
+        attacker.modifiers.damage_scale *= 1. - self.falloff * progress;
+
+        if land(&attacker, &SWORD_AURA, body).is_none() {
+            return;
+        }
+
+        self.pierced += 1;
+
+        if self.max_pierce >= 0 && self.pierced > self.max_pierce {
+            self.shatter();
         }
     }
 }
 
+impl Pooled for SwordAura {
+    fn reset(&mut self) {
+        self.released = false;
+        self.attacker = None;
+        self.reach = 1.;
+        self.travelled = 0.;
+        self.pierced = 0;
+        self.stopped = false;
+        self.shattered = false;
+
+        let scale = self.base().get_scale();
+        let width = self.width;
+
+        self.base_mut().set_scale(Vector2::new(scale.x, width));
+
+        self.effect().bind_mut().restart();
+        self.base()
+            .get_node_as::<Hitbox>("AuraCollision")
+            .bind_mut()
+            .begin_swing();
+    }
+
+    fn release(&mut self) {
+        self.released = true;
+        park(&mut self.base_mut());
+    }
+
+    fn released(&self) -> bool {
+        self.released
+    }
+}
+
 #[godot_api]
 impl INode2D for SwordAura {
-    fn physics_process(&mut self, _delta: f64) {
-        let mut fragment = self.base().get_node_as::<GpuParticles2D>("AuraParticles");
-        let position = self.base().get_position();
-        let flipped = self.flipped;
+    fn ready(&mut self) {
+        let scale = self.base().get_scale();
+        let width = self.width;
 
-        self.base_mut()
-            .set_position(position + Vector2::new(if flipped { -10. } else { 10. }, 0.));
+        self.base_mut().set_scale(Vector2::new(scale.x, width));
+    }
 
-        if self.delay {
+    fn physics_process(&mut self, delta: f64) {
+        if self.stopped {
             return;
         }
 
-        fragment.set_emitting(false);
+        let direction = if self.facing().bind().left() { -1. } else { 1. };
+        let step = self.speed * delta as f32 * direction;
+
+        if self.hits_wall(step) {
+            if self.shatter_on_wall {
+                self.shatter();
+            } else {
+                self.stopped = true;
+                self.effect().bind_mut().fade();
+            }
+
+            return;
+        }
 
-        let mut sprite = self.base().get_node_as::<Sprite2D>("SwordAura");
+        let position = self.base().get_position();
 
-        let mut modulate = sprite.get_modulate();
-        let opacity = modulate.a8();
+        self.base_mut()
+            .set_position(position + Vector2::new(step, 0.));
+        self.travelled += step.abs();
 
-        if (opacity as i32 - 30) < 0 {
-            self.base_mut().call_deferred("queue_free", &[]);
-        } else if opacity > 0 {
-            modulate.set_a8(opacity - 30);
-            sprite.set_modulate(modulate);
+        if self.travelled >= self.max_range() {
+            self.effect().bind_mut().fade();
         }
     }
 }
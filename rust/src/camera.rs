@@ -1,11 +1,17 @@
-use crate::player::Player;
+use crate::{
+    physics::{damp_vector, decay},
+    player::Player,
+};
 use godot::prelude::*;
 use rand::Rng;
 
 #[derive(GodotClass)]
 #[class(init, base=Camera2D)]
 pub struct SideCamera {
-    shake: i32,
+    shake: f32,
+    #[export]
+    #[init(val = 180.)]
+    shake_decay: f32,
 
     base: Base<Camera2D>,
 }
@@ -14,33 +20,33 @@ pub struct SideCamera {
 impl SideCamera {
     #[func]
     pub fn shake(&mut self, power: i32) {
-        self.shake = (self.shake + power).min(200);
+        self.shake = (self.shake + power as f32).min(200.);
     }
 }
 
 #[godot_api]
 impl ICamera2D for SideCamera {
-    fn physics_process(&mut self, _delta: f64) {
+    fn physics_process(&mut self, delta: f64) {
         let parent = self.base_mut().get_parent().unwrap();
 
         let position = self.base().get_position();
         let player: Gd<Player> = parent.get_node_as("Player");
 
-        let power = if self.shake > 2 {
+        let power = if self.shake > 2. {
             rand::rng().random_range(-self.shake..=self.shake)
         } else {
-            0
+            0.
         };
 
-        let player_position = player.get_position() + Vector2::new(0., -200. + power as f32);
+        let player_position = player.get_position() + Vector2::new(0., -200. + power);
 
         let target = if player.bind().get_dash_attacking() || player.bind().get_fall_attacking() {
             player_position
         } else {
-            position.lerp(player_position, 0.1)
+            damp_vector(position, player_position, 0.1, delta)
         };
 
         self.base_mut().set_position(target);
-        self.shake = (self.shake - 3).max(0);
+        self.shake = decay(self.shake, self.shake_decay, delta);
     }
 }
@@ -0,0 +1,66 @@
+use crate::player::Player;
+use godot::{
+    classes::{Area2D, IArea2D},
+    prelude::*,
+};
+
+#[derive(GodotClass)]
+#[class(init, base=Area2D)]
+pub struct Checkpoint {
+    #[export]
+    reset_enemies: bool,
+    #[export]
+    #[init(val = true)]
+    save: bool,
+
+    base: Base<Area2D>,
+}
+
+#[godot_api]
+impl Checkpoint {
+    #[signal]
+    fn activated();
+
+    #[func]
+    fn on_body_entered(&mut self, body: Gd<Node2D>) {
+        let Ok(mut player) = body.try_cast::<Player>() else {
+            return;
+        };
+
+        if player.bind().get_hp() <= 0. {
+            return;
+        }
+
+        let position = self.base().get_global_position();
+
+        if player.bind().checkpoint() == position {
+            return;
+        }
+
+        player
+            .bind_mut()
+            .set_checkpoint(position, self.reset_enemies);
+        self.base_mut().emit_signal("activated", &[]);
+
+        if !self.save {
+            return;
+        }
+
+        if let Some(mut save) = self
+            .base()
+            .get_tree()
+            .and_then(|tree| tree.get_first_node_in_group("save"))
+        {
+            save.call_deferred("save_game", &[]);
+        }
+    }
+}
+
+#[godot_api]
+impl IArea2D for Checkpoint {
+    fn ready(&mut self) {
+        let callable = self.base().callable("on_body_entered");
+
+        self.base_mut().connect("body_entered", &callable);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{
+    attack::Attack,
+    audio::{play_sfx, Sfx},
+    damage::{show_damage, DamageKind},
+    enemy::Enemy,
+    player::Player,
+    status::StatusEffect,
+    team::{hostile, Team},
+};
+use godot::prelude::*;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Modifiers {
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+    pub backstab_multiplier: f32,
+    pub aerial_multiplier: f32,
+    // Product of the attacker's buffs and debuffs.
+    pub damage_scale: f32,
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        Self {
+            crit_chance: 0.,
+            crit_multiplier: 1.,
+            backstab_multiplier: 1.,
+            aerial_multiplier: 1.,
+            damage_scale: 1.,
+        }
+    }
+}
+
+pub struct Strike {
+    pub attacker: Vector2,
+    pub target: Vector2,
+    pub target_flipped: bool,
+    pub aerial: bool,
+    pub resistance: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    pub damage: f32,
+    pub kind: DamageKind,
+    pub critical: bool,
+    pub backstab: bool,
+    pub aerial: bool,
+}
+
+impl Hit {
+    pub fn to_dictionary(self) -> Dictionary {
+        dict! {
+            "damage": self.damage,
+            "critical": self.critical,
+            "backstab": self.backstab,
+            "aerial": self.aerial,
+        }
+    }
+}
+
+pub fn resolve(attack: &Attack, modifiers: &Modifiers, strike: &Strike) -> Hit {
+    // A flipped body faces left, so anything on its right is behind it.
+    let backstab = if strike.target_flipped {
+        strike.attacker.x > strike.target.x
+    } else {
+        strike.attacker.x < strike.target.x
+    };
+    let critical = rand::rng().random::<f32>() < modifiers.crit_chance;
+
+    let mut damage = attack.damage(strike.resistance) * modifiers.damage_scale;
+
+    if critical {
+        damage *= modifiers.crit_multiplier;
+    }
+
+    if backstab {
+        damage *= modifiers.backstab_multiplier;
+    }
+
+    if strike.aerial {
+        damage *= modifiers.aerial_multiplier;
+    }
+
+    Hit {
+        damage,
+        kind: if critical {
+            DamageKind::Critical
+        } else if strike.resistance {
+            DamageKind::Resisted
+        } else {
+            DamageKind::Normal
+        },
+        critical,
+        backstab,
+        aerial: strike.aerial,
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Attacker {
+    pub id: InstanceId,
+    pub team: Team,
+    pub friendly_fire: bool,
+    pub position: Vector2,
+    pub aerial: bool,
+    pub modifiers: Modifiers,
+}
+
+pub enum Target {
+    Player(Gd<Player>),
+    Enemy(Gd<Enemy>),
+}
+
+impl Target {
+    pub fn from_node(node: Gd<Node2D>) -> Option<Self> {
+        match node.try_cast::<Player>() {
+            Ok(player) => Some(Target::Player(player)),
+            Err(node) => node.try_cast::<Enemy>().ok().map(Target::Enemy),
+        }
+    }
+
+    pub fn node(&self) -> Gd<Node2D> {
+        match self {
+            Target::Player(player) => player.clone().upcast(),
+            Target::Enemy(enemy) => enemy.clone().upcast(),
+        }
+    }
+
+    pub fn team(&self) -> Team {
+        match self {
+            Target::Player(player) => player.bind().get_team(),
+            Target::Enemy(enemy) => enemy.bind().get_team(),
+        }
+    }
+
+    pub fn hp(&self) -> f32 {
+        match self {
+            Target::Player(player) => player.bind().get_hp(),
+            Target::Enemy(enemy) => enemy.bind().get_hp(),
+        }
+    }
+
+    pub fn invincible(&self) -> bool {
+        match self {
+            Target::Player(player) => player.bind().get_invincible(),
+            Target::Enemy(enemy) => enemy.bind().get_invincible(),
+        }
+    }
+
+    pub fn resistance(&self) -> bool {
+        match self {
+            Target::Player(player) => player.bind().get_resistance(),
+            Target::Enemy(enemy) => enemy.bind().get_resistance(),
+        }
+    }
+
+    pub fn flipped(&self) -> bool {
+        match self {
+            Target::Player(player) => player.bind().get_flipped(),
+            Target::Enemy(enemy) => enemy.bind().get_flipped(),
+        }
+    }
+
+    fn damage(&mut self, damage: f32) {
+        let hp = self.hp() - damage;
+
+        match self {
+            Target::Player(player) => player.bind_mut().set_hp(hp),
+            Target::Enemy(enemy) => enemy.bind_mut().set_hp(hp),
+        }
+    }
+
+    fn apply_effects(&mut self, effects: &[StatusEffect]) {
+        match self {
+            Target::Player(player) => player.bind_mut().apply_effects(effects),
+            Target::Enemy(enemy) => enemy.bind_mut().apply_effects(effects),
+        }
+    }
+
+    fn react(&mut self, impulse: Vector2, hitstun: f64, effects: &[StatusEffect]) {
+        match self {
+            Target::Player(player) => {
+                let mut player = player.bind_mut();
+
+                player.set_hit(true);
+                player.apply_effects(effects);
+                player.knock_back(impulse, hitstun);
+            }
+            Target::Enemy(enemy) => {
+                let mut enemy = enemy.bind_mut();
+
+                enemy.set_hit(true);
+                enemy.apply_effects(effects);
+                enemy.knock_back(impulse, hitstun);
+            }
+        }
+    }
+}
+
+// Applies an attack to whatever body was touched, if its team can be hurt.
+pub fn land(attacker: &Attacker, attack: &Attack, body: Gd<Node2D>) -> Option<Hit> {
+    if body.instance_id() == attacker.id {
+        return None;
+    }
+
+    let mut target = Target::from_node(body)?;
+
+    if !hostile(attacker.team, target.team(), attacker.friendly_fire)
+        || target.invincible()
+        || target.hp() <= 0.
+    {
+        return None;
+    }
+
+    let node = target.node();
+    let resistance = target.resistance();
+
+    let hit = resolve(
+        attack,
+        &attacker.modifiers,
+        &Strike {
+            attacker: attacker.position,
+            target: node.get_position(),
+            target_flipped: target.flipped(),
+            aerial: attacker.aerial,
+            resistance,
+        },
+    );
+
+    target.damage(hit.damage);
+    show_damage(
+        node.get_tree(),
+        node.get_global_position(),
+        hit.damage,
+        hit.kind,
+    );
+    play_sfx(node.get_tree(), Sfx::Hit, node.get_global_position());
+
+    if !resistance {
+        target.react(
+            attack.impulse(attacker.position, node.get_position()),
+            attack.hitstun,
+            attack.effects,
+        );
+    }
+
+    Some(hit)
+}
+
+// Damage over time from hazards, without the flinch or knockback of a hit.
+pub fn afflict(
+    attacker: &Attacker,
+    damage: f32,
+    effects: &[StatusEffect],
+    body: Gd<Node2D>,
+) -> bool {
+    if body.instance_id() == attacker.id {
+        return false;
+    }
+
+    let Some(mut target) = Target::from_node(body) else {
+        return false;
+    };
+
+    if !hostile(attacker.team, target.team(), attacker.friendly_fire)
+        || target.invincible()
+        || target.hp() <= 0.
+    {
+        return false;
+    }
+
+    let node = target.node();
+    let damage = damage * attacker.modifiers.damage_scale;
+
+    target.damage(damage);
+    target.apply_effects(effects);
+    show_damage(
+        node.get_tree(),
+        node.get_global_position(),
+        damage,
+        DamageKind::Status,
+    );
+
+    true
+}
@@ -1,52 +1,100 @@
-use godot::{
-    classes::{GpuParticles2D, Sprite2D, Timer},
-    obj::WithBaseField,
-    prelude::*,
+use crate::{
+    combat::{afflict, Attacker},
+    effect::TimedEffect,
+    pool::{park, Pooled},
+    status::StatusEffect,
 };
+use godot::{classes::Area2D, obj::WithBaseField, prelude::*};
 
 #[derive(GodotClass)]
 #[class(init, base=Node2D)]
 pub struct GroundCrack {
-    #[init(val = true)]
-    delay: bool,
+    // Hurts and slows hostile bodies standing on the crack while it is visible.
+    #[export]
+    hazard: bool,
+    #[export]
+    #[init(val = 5.)]
+    hazard_damage: f32,
+    #[export]
+    #[init(val = 0.5)]
+    hazard_interval: f64,
+    #[export]
+    #[init(val = 0.6)]
+    hazard_slow: f32,
+    attacker: Option<Attacker>,
+    hazard_time: f64,
+    released: bool,
 
     base: Base<Node2D>,
 }
 
 #[godot_api]
 impl GroundCrack {
+    pub fn set_attacker(&mut self, attacker: Attacker) {
+        self.attacker = Some(attacker);
+    }
+
     #[func]
-    fn on_timeout(&mut self) {
-        self.delay = false;
+    fn release(&mut self) {
+        Pooled::release(self);
     }
-}
 
-#[godot_api]
-impl INode2D for GroundCrack {
-    fn physics_process(&mut self, _delta: f64) {
-        let mut fragment = self
-            .base()
-            .get_node_as::<GpuParticles2D>("FragmentParticles");
-        let timer = self.base().get_node_as::<Timer>("Timer");
+    fn update_hazard(&mut self, delta: f64) {
+        let Some(mut attacker) = self.attacker.filter(|_| self.hazard) else {
+            return;
+        };
 
-        if timer.get_time_left() < 2.9 {
-            fragment.set_emitting(false);
-        }
+        self.hazard_time -= delta;
 
-        if self.delay {
+        if self.hazard_time > 0. {
             return;
         }
 
-        let mut sprite = self.base().get_node_as::<Sprite2D>("GroundCrack");
+        self.hazard_time += self.hazard_interval;
+        attacker.position = self.base().get_position();
 
-        let mut modulate = sprite.get_modulate();
-        let opacity = modulate.a8();
+        // Outlasts the interval so the slow holds while standing on the crack.
+        let effects = [StatusEffect::slow(
+            self.hazard_interval * 2.,
+            self.hazard_slow,
+        )];
 
-        if opacity > 0 {
-            modulate.set_a8(opacity - 1);
-            sprite.set_modulate(modulate);
-        } else {
-            self.base_mut().call_deferred("queue_free", &[]);
+        for body in self
+            .base()
+            .get_node_as::<Area2D>("HazardZone")
+            .get_overlapping_bodies()
+            .iter_shared()
+        {
+            afflict(&attacker, self.hazard_damage, &effects, body);
         }
     }
 }
+
+impl Pooled for GroundCrack {
+    fn reset(&mut self) {
+        self.released = false;
+        self.attacker = None;
+        self.hazard_time = 0.;
+
+        self.base()
+            .get_node_as::<TimedEffect>("Effect")
+            .bind_mut()
+            .restart();
+    }
+
+    fn release(&mut self) {
+        self.released = true;
+        park(&mut self.base_mut());
+    }
+
+    fn released(&self) -> bool {
+        self.released
+    }
+}
+
+#[godot_api]
+impl INode2D for GroundCrack {
+    fn physics_process(&mut self, delta: f64) {
+        self.update_hazard(delta);
+    }
+}
@@ -0,0 +1,167 @@
+use godot::{
+    classes::{AnimatedSprite2D, Font, Shader, ShaderMaterial, ThemeDB},
+    prelude::*,
+};
+use rand::Rng;
+
+const CAPACITY: usize = 32;
+const LIFETIME: f32 = 0.8;
+const RISE: f32 = 60.;
+
+const FLASH_SHADER: &str = "shader_type canvas_item;
+
+uniform float flash : hint_range(0.0, 1.0) = 0.0;
+uniform vec4 flash_color : source_color = vec4(1.0);
+
+void fragment() {
+    COLOR.rgb = mix(COLOR.rgb, flash_color.rgb, flash);
+}
+";
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum DamageKind {
+    #[default]
+    Normal,
+    Resisted,
+    Critical,
+    Status,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Popup {
+    position: Vector2,
+    amount: f32,
+    kind: DamageKind,
+    age: f32,
+    active: bool,
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Node2D)]
+pub struct DamageNumbers {
+    #[init(val = [Popup::default(); CAPACITY])]
+    popups: [Popup; CAPACITY],
+    font: Option<Gd<Font>>,
+
+    base: Base<Node2D>,
+}
+
+impl DamageNumbers {
+    pub fn spawn(&mut self, position: Vector2, amount: f32, kind: DamageKind) {
+        let index = self
+            .popups
+            .iter()
+            .position(|popup| !popup.active)
+            .unwrap_or_else(|| {
+                self.popups
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| a.age.total_cmp(&b.age))
+                    .map(|(index, _)| index)
+                    .unwrap_or(0)
+            });
+
+        let jitter = rand::rng().random_range(-20.0..20.0);
+
+        self.popups[index] = Popup {
+            position: position + Vector2::new(jitter, -80.),
+            amount,
+            kind,
+            age: 0.,
+            active: true,
+        };
+    }
+}
+
+#[godot_api]
+impl INode2D for DamageNumbers {
+    fn ready(&mut self) {
+        self.font = ThemeDB::singleton().get_fallback_font();
+
+        self.base_mut().add_to_group("damage_numbers");
+    }
+
+    fn process(&mut self, delta: f64) {
+        for popup in self.popups.iter_mut().filter(|popup| popup.active) {
+            popup.age += delta as f32;
+            popup.active = popup.age < LIFETIME;
+        }
+
+        self.base_mut().queue_redraw();
+    }
+
+    fn draw(&mut self) {
+        let Some(font) = self.font.clone() else {
+            return;
+        };
+
+        let popups = self.popups;
+
+        for popup in popups.iter().filter(|popup| popup.active) {
+            let (mut color, size) = match popup.kind {
+                DamageKind::Normal => (Color::WHITE, 24),
+                DamageKind::Resisted => (Color::LIGHT_STEEL_BLUE, 20),
+                DamageKind::Critical => (Color::GOLD, 32),
+                DamageKind::Status => (Color::ORANGE, 18),
+            };
+
+            color.a = 1. - popup.age / LIFETIME;
+
+            let text = format!("{}", popup.amount.round() as i32);
+            let position = popup.position - Vector2::new(0., RISE * popup.age);
+
+            self.base_mut()
+                .draw_string_outline_ex(&font, position, &text)
+                .font_size(size)
+                .size(4)
+                .modulate(Color::from_rgba(0., 0., 0., color.a))
+                .done();
+            self.base_mut()
+                .draw_string_ex(&font, position, &text)
+                .font_size(size)
+                .modulate(color)
+                .done();
+        }
+    }
+}
+
+pub fn show_damage(tree: Option<Gd<SceneTree>>, position: Vector2, amount: f32, kind: DamageKind) {
+    let numbers = tree
+        .and_then(|tree| tree.get_first_node_in_group("damage_numbers"))
+        .and_then(|numbers| numbers.try_cast::<DamageNumbers>().ok());
+
+    if let Some(mut numbers) = numbers {
+        numbers.bind_mut().spawn(position, amount, kind);
+    }
+}
+
+#[derive(Default)]
+pub struct Flash {
+    material: Option<Gd<ShaderMaterial>>,
+    amount: f32,
+}
+
+impl Flash {
+    pub fn attach(&mut self, sprite: &mut Gd<AnimatedSprite2D>) {
+        let mut shader = Shader::new_gd();
+        shader.set_code(FLASH_SHADER);
+
+        let mut material = ShaderMaterial::new_gd();
+        material.set_shader(&shader);
+
+        sprite.set_material(&material);
+        self.material = Some(material);
+    }
+
+    pub fn trigger(&mut self) {
+        self.amount = 1.;
+    }
+
+    pub fn update(&mut self, delta: f64) {
+        self.amount = (self.amount - delta as f32 * 8.).max(0.);
+
+        if let Some(material) = self.material.as_mut() {
+            material.set_shader_parameter("flash", &self.amount.to_variant());
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use godot::{
+    classes::{CanvasItem, Curve, GpuParticles2D, INode},
+    prelude::*,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Phase {
+    Spawn,
+    Hold,
+    Fade,
+    Despawn,
+}
+
+// Drives the spawn, hold, fade and despawn phases of a visual effect. Paths
+// are relative to the parent, which owns the effect and decides what
+// despawning means for it.
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+pub struct TimedEffect {
+    #[export]
+    target: NodePath,
+    #[export]
+    particles: Array<NodePath>,
+    // Fades the target in from transparent.
+    #[export]
+    spawn_duration: f64,
+    // Negative holds until `fade` is called.
+    #[export]
+    #[init(val = 1.)]
+    hold_duration: f64,
+    #[export]
+    #[init(val = 1.)]
+    fade_duration: f64,
+    // Seconds after spawning when the particles stop, negative for when the
+    // fade starts.
+    #[export]
+    #[init(val = -1.)]
+    particle_time: f64,
+    // Both are sampled over the fade, from 0 to 1. Alpha falls linearly to 0
+    // and the scale is kept without a curve.
+    #[export]
+    alpha_curve: Option<Gd<Curve>>,
+    #[export]
+    scale_curve: Option<Gd<Curve>>,
+
+    #[init(val = Phase::Spawn)]
+    phase: Phase,
+    time: f64,
+    duration: f64,
+    elapsed: f64,
+    alpha: f32,
+    scale: Vector2,
+
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl TimedEffect {
+    #[signal]
+    fn spawning();
+
+    #[signal]
+    fn holding();
+
+    #[signal]
+    fn fading();
+
+    #[signal]
+    fn despawned();
+
+    #[func]
+    pub fn restart(&mut self) {
+        self.elapsed = 0.;
+
+        for mut particles in self.particle_nodes() {
+            particles.restart();
+        }
+
+        if let Some(mut target) = self.target_node() {
+            let mut modulate = target.get_modulate();
+
+            modulate.a = self.alpha;
+            target.set_modulate(modulate);
+
+            if let Ok(mut target) = target.try_cast::<Node2D>() {
+                target.set_scale(self.scale);
+            }
+        }
+
+        self.enter(Phase::Spawn);
+        self.update(0.);
+    }
+
+    #[func]
+    pub fn fade(&mut self) {
+        self.fade_within(self.fade_duration);
+    }
+
+    // Fades out now, over a duration that only applies to this fade.
+    #[func]
+    pub fn fade_within(&mut self, duration: f64) {
+        if matches!(self.phase, Phase::Spawn | Phase::Hold) {
+            self.duration = duration;
+            self.enter(Phase::Fade);
+        }
+    }
+
+    fn enter(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.time = 0.;
+
+        let signal = match phase {
+            Phase::Spawn => "spawning",
+            Phase::Hold => "holding",
+            Phase::Fade => {
+                if self.particle_time < 0. {
+                    self.stop_particles();
+                }
+
+                "fading"
+            }
+            Phase::Despawn => "despawned",
+        };
+
+        self.base_mut().emit_signal(signal, &[]);
+    }
+
+    fn target_node(&self) -> Option<Gd<CanvasItem>> {
+        self.base()
+            .get_parent()?
+            .try_get_node_as::<CanvasItem>(self.target.clone())
+    }
+
+    fn particle_nodes(&self) -> Vec<Gd<GpuParticles2D>> {
+        let Some(parent) = self.base().get_parent() else {
+            return Vec::new();
+        };
+
+        self.particles
+            .iter_shared()
+            .filter_map(|path| parent.try_get_node_as::<GpuParticles2D>(path))
+            .collect()
+    }
+
+    fn stop_particles(&mut self) {
+        for mut particles in self.particle_nodes() {
+            particles.set_emitting(false);
+        }
+    }
+
+    fn apply(&mut self, alpha: f32, scale: f32) {
+        let Some(mut target) = self.target_node() else {
+            return;
+        };
+
+        let mut modulate = target.get_modulate();
+
+        modulate.a = alpha.clamp(0., 1.);
+        target.set_modulate(modulate);
+
+        if let Ok(mut target) = target.try_cast::<Node2D>() {
+            target.set_scale(self.scale * scale);
+        }
+    }
+
+    fn update(&mut self, delta: f64) {
+        self.time += delta;
+        self.elapsed += delta;
+
+        let previous = self.elapsed - delta;
+
+        if self.particle_time >= 0.
+            && previous <= self.particle_time
+            && self.elapsed > self.particle_time
+        {
+            self.stop_particles();
+        }
+
+        match self.phase {
+            Phase::Spawn => {
+                let progress = progress(self.time, self.spawn_duration);
+
+                self.apply(self.alpha * progress, 1.);
+
+                if progress >= 1. {
+                    self.enter(Phase::Hold);
+                }
+            }
+            Phase::Hold => {
+                if self.hold_duration >= 0. && self.time >= self.hold_duration {
+                    self.fade();
+                }
+            }
+            Phase::Fade => {
+                let progress = progress(self.time, self.duration);
+                let alpha = match &self.alpha_curve {
+                    Some(curve) => curve.sample(progress) * self.alpha,
+                    None => (1. - progress) * self.alpha,
+                };
+                let scale = match &self.scale_curve {
+                    Some(curve) => curve.sample(progress),
+                    None => 1.,
+                };
+
+                self.apply(alpha, scale);
+
+                if progress >= 1. {
+                    self.enter(Phase::Despawn);
+                }
+            }
+            Phase::Despawn => {}
+        }
+    }
+}
+
+// Share of a phase that has run after `time` seconds, from 0 to 1.
+fn progress(time: f64, duration: f64) -> f32 {
+    if duration > 0. {
+        (time / duration).min(1.) as f32
+    } else {
+        1.
+    }
+}
+
+#[godot_api]
+impl INode for TimedEffect {
+    fn ready(&mut self) {
+        if let Some(target) = self.target_node() {
+            self.alpha = target.get_modulate().a;
+            self.scale = target
+                .try_cast::<Node2D>()
+                .map_or(Vector2::ONE, |target| target.get_scale());
+        }
+
+        self.restart();
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        self.update(delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{
+        tests::{run, EPSILON, RATES},
+        REFERENCE_RATE,
+    };
+
+    // Progress of a phase after one second at `rate`, stepped like `update`.
+    fn progress_after_second(rate: f64, duration: f64) -> f32 {
+        progress(run(rate, 0., |time, delta| time + delta), duration)
+    }
+
+    #[test]
+    fn progress_matches_reference_ticks() {
+        for duration in [0.5, 2., 3.] {
+            let expected = progress_after_second(REFERENCE_RATE as f64, duration);
+
+            for rate in RATES {
+                let value = progress_after_second(rate, duration);
+
+                assert!(
+                    (value - expected).abs() < EPSILON,
+                    "{value} at {rate} Hz, expected {expected}",
+                );
+            }
+        }
+
+        assert!((progress_after_second(30., 2.) - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn progress_is_clamped() {
+        assert_eq!(progress(3., 2.), 1.);
+        assert_eq!(progress(0., 0.), 1.);
+        assert_eq!(progress(0., 2.), 0.);
+    }
+}
@@ -1,13 +1,35 @@
-use crate::{camera::SideCamera, hitbox::Hitbox, player::Player};
+use crate::{
+    attack::{Attack, ENEMY_ATTACK1, ENEMY_ATTACK2},
+    audio::{play_sfx, Sfx},
+    camera::SideCamera,
+    combat::{land, Attacker, Modifiers},
+    damage::{show_damage, DamageKind, Flash},
+    facing::Facing,
+    hitbox::Hitbox,
+    knockback::Knockback,
+    marker::{marker, parse, AnimationEvents, Marker},
+    physics::{self, damp, fall, regen},
+    platform::DropThrough,
+    player::Player,
+    status::{StatusEffect, StatusEffects},
+    team::Team,
+};
 use godot::{
-    classes::{
-        AnimatedSprite2D, CharacterBody2D, CollisionShape2D, ICharacterBody2D, ProjectSettings,
-        Timer,
-    },
+    classes::{AnimatedSprite2D, CharacterBody2D, CollisionShape2D, ICharacterBody2D, Timer},
     prelude::*,
 };
 use rand::Rng;
 
+const HITBOXES: [&str; 2] = ["Attack1", "Attack2"];
+const MARKERS: &[Marker] = &[
+    marker("attack1", 3, "hitbox:Attack1/LowerCollision"),
+    marker("attack1", 4, "hitbox:Attack1/UpperCollision"),
+    marker("attack1", 7, "hitbox_off"),
+    marker("attack2", 4, "hitbox:Attack2/Collision"),
+    marker("attack2", 4, "shake:50"),
+    marker("attack2", 6, "hitbox_off"),
+];
+
 #[derive(GodotClass)]
 #[class(init, base=CharacterBody2D)]
 pub struct Enemy {
@@ -16,17 +38,49 @@ pub struct Enemy {
     hp: f32,
     #[init(val = 250.)]
     speed: f32,
+    #[export]
+    #[init(val = 3.)]
+    hp_regen: f32,
+    #[export]
+    boss: bool,
+    #[export]
+    display_name: GString,
+    // Give enemies of one encounter different teams, or friendly fire, to make them infight.
+    #[export]
+    #[init(val = Team::Enemy)]
+    team: Team,
+    #[export]
+    friendly_fire: bool,
+    #[export]
+    #[init(val = 0.05)]
+    crit_chance: f32,
+    #[export]
+    #[init(val = 1.5)]
+    crit_multiplier: f32,
+    #[export]
+    #[init(val = 1.)]
+    weight: f32,
+    #[export]
+    #[init(val = 3)]
+    max_juggles: u32,
+    #[export]
+    #[init(val = 0.6)]
+    wall_bounce: f32,
     inconstancy: f32,
+    // Global, so it holds wherever the enemy is parented.
+    spawn: Vector2,
     #[var]
     resistance: bool,
     #[var]
     invincible: bool,
 
+    #[var]
     flipped: bool,
     falling: bool,
     #[var]
     hit: bool,
     suffering: bool,
+    #[var]
     aggro: bool,
     attacking1: bool,
     attacking2: bool,
@@ -35,7 +89,14 @@ pub struct Enemy {
     attack1_delay: bool,
     attack2_delay: bool,
 
-    attack2_shook: bool,
+    events: AnimationEvents,
+    flash: Flash,
+    // The hp the body was authored with, restored on respawn.
+    max_hp: f32,
+    last_hp: f32,
+    drop: DropThrough,
+    statuses: StatusEffects,
+    knockback: Knockback,
 
     base: Base<CharacterBody2D>,
 }
@@ -43,7 +104,52 @@ pub struct Enemy {
 #[godot_api]
 impl Enemy {
     #[signal]
-    fn flip();
+    fn hit_landed(target: Gd<Node2D>, hit: Dictionary);
+
+    fn face(&mut self, flipped: bool) {
+        self.flipped = flipped;
+        self.base()
+            .get_node_as::<Facing>("Facing")
+            .bind_mut()
+            .set_facing(if flipped { -1 } else { 1 });
+    }
+
+    fn disarm(&mut self) {
+        for name in HITBOXES {
+            self.base().get_node_as::<Hitbox>(name).bind_mut().disarm();
+        }
+    }
+
+    fn begin_swing(&mut self) {
+        for name in HITBOXES {
+            let mut hitbox = self.base().get_node_as::<Hitbox>(name);
+            let mut hitbox = hitbox.bind_mut();
+
+            hitbox.disarm();
+            hitbox.begin_swing();
+        }
+    }
+
+    fn on_animation_event(&mut self, event: &str) {
+        match parse(event) {
+            ("hitbox", Some(path)) => {
+                self.disarm();
+                self.base()
+                    .get_node_as::<CollisionShape2D>(path)
+                    .set_disabled(false);
+            }
+            ("hitbox_off", _) => self.disarm(),
+            ("shake", Some(power)) => {
+                self.base()
+                    .get_parent()
+                    .unwrap()
+                    .get_node_as::<SideCamera>("SideCamera")
+                    .bind_mut()
+                    .shake(power.parse().unwrap_or_default());
+            }
+            _ => godot_warn!("Unknown animation event {event}."),
+        }
+    }
 
     fn play_animation(&mut self, new: &str) {
         let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
@@ -53,6 +159,12 @@ impl Enemy {
         if old != new {
             self.on_animation_changed(old.as_str(), new);
 
+            if new.starts_with("attack") {
+                let position = self.base().get_global_position();
+
+                play_sfx(self.base().get_tree(), Sfx::Swing, position);
+            }
+
             animated.set_animation(new);
             animated.play();
         }
@@ -90,34 +202,70 @@ impl Enemy {
 
     #[func]
     fn on_attack1_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Player>() else {
-            return;
-        };
-
-        let hp: f32 = body.bind().get_hp();
+        self.strike("Attack1", body, &ENEMY_ATTACK1);
+    }
 
-        if !body.bind().get_invincible() && hp > 0. {
-            body.bind_mut().set_hit(true);
-            body.bind_mut().set_hp(hp - 15.);
+    #[func]
+    fn on_attack2_body_entered(&mut self, body: Gd<Node2D>) {
+        self.strike("Attack2", body, &ENEMY_ATTACK2);
+    }
 
-            body.set_velocity(Vector2::new(if self.flipped { -200. } else { 200. }, -400.));
+    fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            crit_chance: self.crit_chance,
+            crit_multiplier: self.crit_multiplier,
+            damage_scale: self.statuses.damage_scale(),
+            ..Default::default()
         }
     }
 
-    #[func]
-    fn on_attack2_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Player>() else {
-            return;
+    fn strike(&mut self, hitbox: &str, body: Gd<Node2D>, attack: &Attack) {
+        let hitbox = self.base().get_node_as::<Hitbox>(hitbox);
+        let attacker = Attacker {
+            id: self.base().instance_id(),
+            team: hitbox.bind().get_team(),
+            friendly_fire: hitbox.bind().get_friendly_fire(),
+            position: self.base().get_position(),
+            aerial: !self.base().is_on_floor(),
+            modifiers: self.modifiers(),
         };
 
-        let hp: f32 = body.bind().get_hp();
+        if let Some(hit) = land(&attacker, attack, body.clone()) {
+            self.base_mut().emit_signal(
+                "hit_landed",
+                &[body.to_variant(), hit.to_dictionary().to_variant()],
+            );
+        }
+    }
 
-        if !body.bind().get_invincible() && hp > 0. {
-            body.bind_mut().set_hit(true);
-            body.bind_mut().set_hp(hp - 35.);
+    pub fn apply_effects(&mut self, effects: &[StatusEffect]) {
+        self.statuses.apply_all(effects);
+    }
 
-            body.set_velocity(Vector2::new(if self.flipped { -400. } else { 400. }, 800.));
-        }
+    pub fn knock_back(&mut self, impulse: Vector2, hitstun: f64) {
+        self.knockback
+            .launch(impulse, hitstun, self.weight, self.max_juggles);
+    }
+
+    pub fn reset(&mut self) {
+        let spawn = self.spawn;
+
+        self.statuses.clear();
+        self.knockback.clear();
+
+        self.hp = self.max_hp;
+        self.hit = false;
+        self.suffering = false;
+        self.aggro = false;
+        self.falling = false;
+        self.resistance = false;
+        self.invincible = false;
+        self.attacking1 = false;
+        self.attacking2 = false;
+
+        self.base_mut().set_global_position(spawn);
+        self.base_mut().set_velocity(Vector2::ZERO);
+        self.play_animation("idle");
     }
 
     #[func]
@@ -139,6 +287,24 @@ impl Enemy {
 #[godot_api]
 impl ICharacterBody2D for Enemy {
     fn ready(&mut self) {
+        let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
+        let mut body = self.to_gd().upcast::<CharacterBody2D>();
+
+        self.flash.attach(&mut animated);
+        self.drop.attach(&mut body);
+        self.max_hp = self.hp;
+        self.last_hp = self.hp;
+        self.spawn = self.base().get_global_position();
+
+        self.base_mut().add_to_group("enemy");
+
+        for name in HITBOXES {
+            self.base()
+                .get_node_as::<Hitbox>(name)
+                .bind_mut()
+                .inherit(self.team, self.friendly_fire);
+        }
+
         let mut rng = rand::rng();
 
         self.inconstancy = rng.random_range(-50..50) as f32;
@@ -171,34 +337,50 @@ impl ICharacterBody2D for Enemy {
     fn physics_process(&mut self, delta: f64) {
         self.base_mut().queue_redraw();
 
-        let gravity = ProjectSettings::singleton()
-            .get_setting("physics/2d/default_gravity")
-            .to::<f32>()
-            / 35.;
+        if self.hp < self.last_hp {
+            self.flash.trigger();
+        }
+
+        self.last_hp = self.hp;
+        self.flash.update(delta);
+
+        let mut body = self.to_gd().upcast::<CharacterBody2D>();
+
+        self.drop.update(&mut body, delta);
+
+        let status_damage = self.statuses.update(delta);
+
+        if status_damage > 0. && self.hp > 0. {
+            self.hp -= status_damage;
+            show_damage(
+                self.base().get_tree(),
+                self.base().get_global_position(),
+                status_damage,
+                DamageKind::Status,
+            );
+        }
+
+        let gravity = physics::gravity();
 
         let mut velocity = self.base().get_velocity();
         let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
 
-        let attack1 = self.base().get_node_as::<Hitbox>("Attack1");
-        let mut upper_collision = attack1.get_node_as::<CollisionShape2D>("UpperCollision");
-        let mut lower_collision = attack1.get_node_as::<CollisionShape2D>("LowerCollision");
-        let mut attack2_collision = self
-            .base()
-            .get_node_as::<Hitbox>("Attack2")
-            .get_node_as::<CollisionShape2D>("Collision");
+        animated.set_self_modulate(self.statuses.tint());
 
         velocity.y = if !self.base().is_on_floor() {
-            (velocity.y + gravity + delta as f32).min(750.)
+            fall(velocity.y, gravity, 750., delta)
         } else {
             0.
         };
 
+        let knocked = self
+            .knockback
+            .update(&body, &mut velocity, self.wall_bounce, delta);
+
         if self.hp <= 0. {
-            velocity.x = velocity.x.lerp(0., 0.1);
+            velocity.x = damp(velocity.x, 0., 0.1, delta);
 
-            upper_collision.set_disabled(true);
-            lower_collision.set_disabled(true);
-            attack2_collision.set_disabled(true);
+            self.disarm();
 
             self.base_mut().move_and_slide();
             self.base_mut().set_velocity(velocity);
@@ -206,7 +388,7 @@ impl ICharacterBody2D for Enemy {
 
             return;
         } else {
-            self.hp = (self.hp + 0.05).min(100.)
+            self.hp = regen(self.hp, self.hp_regen, self.max_hp, delta)
         }
 
         if !self.attacking2 {
@@ -222,40 +404,16 @@ impl ICharacterBody2D for Enemy {
         let animation = animated.get_animation().to_string();
         let frame = animated.get_frame();
 
-        let mut camera = self
-            .base()
-            .get_parent()
-            .unwrap()
-            .get_node_as::<SideCamera>("SideCamera");
+        let (started, events) = self.events.update(MARKERS, &animation, frame);
 
-        match frame {
-            3 if animation == "attack1" => {
-                upper_collision.set_disabled(true);
-                lower_collision.set_disabled(false);
-                attack2_collision.set_disabled(true);
-            }
-            4..=6 if animation == "attack1" => {
-                upper_collision.set_disabled(false);
-                lower_collision.set_disabled(true);
-                attack2_collision.set_disabled(true);
-            }
-            4..=5 if animation == "attack2" => {
-                upper_collision.set_disabled(true);
-                lower_collision.set_disabled(true);
-                attack2_collision.set_disabled(false);
-
-                if !self.attack2_shook {
-                    self.attack2_shook = true;
-                    camera.bind_mut().shake(50);
-                }
-            }
-            _ => {
-                self.attack2_shook = false;
+        // Covers replays of the same animation as well as switches, so a
+        // restarted attack can hit the same targets again.
+        if started {
+            self.begin_swing();
+        }
 
-                upper_collision.set_disabled(true);
-                lower_collision.set_disabled(true);
-                attack2_collision.set_disabled(true);
-            }
+        for event in events {
+            self.on_animation_event(event);
         }
 
         let magnitude =
@@ -265,6 +423,12 @@ impl ICharacterBody2D for Enemy {
         let facing = (magnitude.x < 0. && self.flipped) || (magnitude.x >= 0. && !self.flipped);
 
         if hp > 0. && (self.hit || (magnitude.x.abs() < 600. && facing)) {
+            if !self.aggro {
+                let position = self.base().get_global_position();
+
+                play_sfx(self.base().get_tree(), Sfx::Aggro, position);
+            }
+
             self.aggro = true;
         }
 
@@ -279,16 +443,27 @@ impl ICharacterBody2D for Enemy {
         let flip_delay = rand::rng().random_range(10..15) as f64;
 
         let attacking = self.attacking1 || self.attacking2;
-        let idling = !self.suffering && !self.falling && !attacking;
+        let stunned = self.statuses.stunned();
+        let idling = !self.suffering && !self.falling && !attacking && !stunned && !knocked;
+        let speed = self.speed * self.statuses.speed_scale();
+
+        if self.aggro
+            && idling
+            && magnitude.y > 100.
+            && magnitude.x.abs() < 300.
+            && self.drop.on_platform(&body)
+        {
+            self.drop.start(&mut body);
+        }
 
         // TODO: Add a projectile to attack2.
         if self.aggro && idling {
             velocity.x = if magnitude.x > 200.0 {
                 self.play_animation("run");
-                self.speed
+                speed
             } else if magnitude.x < -200.0 {
                 self.play_animation("run");
-                -self.speed
+                -speed
             } else if !self.attack2_delay {
                 flip_timer.set_wait_time(flip_delay);
                 flip_timer.start();
@@ -319,10 +494,7 @@ impl ICharacterBody2D for Enemy {
                 let flipped = magnitude.x < 0.;
 
                 if self.flipped != flipped {
-                    self.base_mut().emit_signal("flip", &[]);
-                    self.flipped = flipped;
-
-                    animated.set_flip_h(self.flipped);
+                    self.face(flipped);
                 }
             }
         } else if idling {
@@ -332,11 +504,8 @@ impl ICharacterBody2D for Enemy {
                 flip_timer.set_wait_time(flip_delay);
                 flip_timer.start();
 
-                self.base_mut().emit_signal("flip", &[]);
-                self.flipped = !self.flipped;
+                self.face(!self.flipped);
                 self.flip_delay = true;
-
-                animated.set_flip_h(self.flipped);
             }
 
             velocity.x = 0.;
@@ -354,8 +523,12 @@ impl ICharacterBody2D for Enemy {
             self.falling = false;
         }
 
-        if self.suffering {
-            velocity.x = velocity.x.lerp(0., 0.1);
+        if (self.suffering || stunned) && !knocked {
+            velocity.x = damp(velocity.x, 0., 0.1, delta);
+        }
+
+        if stunned && !self.suffering && !self.falling && !attacking {
+            self.play_animation("idle");
         }
 
         if self.hit {
@@ -0,0 +1,115 @@
+use godot::{
+    classes::{AnimatedSprite2D, CollisionPolygon2D, CollisionShape2D, INode, RayCast2D, Sprite2D},
+    prelude::*,
+};
+
+struct Mirrored {
+    node: Gd<Node2D>,
+    position: Vector2,
+    rotation: f32,
+    scale_sign: f32,
+    flip_h: bool,
+    target_position: Vector2,
+}
+
+// Mirrors the listed siblings around the parent's origin. Everything is
+// authored facing right and derived again on each change, so it cannot drift.
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+pub struct Facing {
+    // Paths are relative to the parent.
+    #[export]
+    targets: Array<NodePath>,
+    #[var]
+    #[init(val = 1)]
+    direction: i32,
+
+    mirrored: Vec<Mirrored>,
+
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl Facing {
+    #[func]
+    pub fn set_facing(&mut self, direction: i32) {
+        self.direction = if direction < 0 { -1 } else { 1 };
+
+        let sign = self.direction as f32;
+        let left = self.direction < 0;
+
+        for mirrored in &mut self.mirrored {
+            let node = &mut mirrored.node;
+
+            node.set_position(Vector2::new(
+                mirrored.position.x * sign,
+                mirrored.position.y,
+            ));
+
+            // Flipping alone only mirrors an unrotated sprite.
+            if let Ok(mut sprite) = node.clone().try_cast::<AnimatedSprite2D>() {
+                sprite.set_flip_h(mirrored.flip_h != left);
+                sprite.set_rotation(mirrored.rotation * sign);
+            } else if let Ok(mut sprite) = node.clone().try_cast::<Sprite2D>() {
+                sprite.set_flip_h(mirrored.flip_h != left);
+                sprite.set_rotation(mirrored.rotation * sign);
+            } else if node.clone().try_cast::<CollisionShape2D>().is_ok()
+                || node.clone().try_cast::<CollisionPolygon2D>().is_ok()
+            {
+                node.set_rotation(mirrored.rotation * sign);
+            } else if let Ok(mut ray) = node.clone().try_cast::<RayCast2D>() {
+                ray.set_target_position(Vector2::new(
+                    mirrored.target_position.x * sign,
+                    mirrored.target_position.y,
+                ));
+            } else {
+                // Keep the magnitude so other systems may resize the node.
+                let scale = node.get_scale();
+
+                node.set_scale(Vector2::new(
+                    scale.x.abs() * mirrored.scale_sign * sign,
+                    scale.y,
+                ));
+            }
+        }
+    }
+
+    pub fn left(&self) -> bool {
+        self.direction < 0
+    }
+}
+
+#[godot_api]
+impl INode for Facing {
+    fn ready(&mut self) {
+        let Some(parent) = self.base().get_parent() else {
+            return;
+        };
+
+        self.mirrored = self
+            .targets
+            .iter_shared()
+            .filter_map(|path| parent.try_get_node_as::<Node2D>(path))
+            .map(|node| Mirrored {
+                position: node.get_position(),
+                rotation: node.get_rotation(),
+                scale_sign: node.get_scale().x.signum(),
+                flip_h: node
+                    .clone()
+                    .try_cast::<AnimatedSprite2D>()
+                    .map(|sprite| sprite.is_flipped_h())
+                    .or_else(|node| {
+                        node.try_cast::<Sprite2D>()
+                            .map(|sprite| sprite.is_flipped_h())
+                    })
+                    .unwrap_or(false),
+                target_position: node
+                    .clone()
+                    .try_cast::<RayCast2D>()
+                    .map(|ray| ray.get_target_position())
+                    .unwrap_or_default(),
+                node,
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,77 @@
+use godot::{
+    classes::{
+        control::{LayoutPreset, MouseFilter},
+        CanvasLayer, ColorRect, ICanvasLayer,
+    },
+    global::move_toward,
+    prelude::*,
+};
+
+#[derive(GodotClass)]
+#[class(init, base=CanvasLayer)]
+pub struct ScreenFade {
+    #[export]
+    #[init(val = Color::BLACK)]
+    color: Color,
+
+    alpha: f32,
+    target: f32,
+    #[init(val = 1.)]
+    duration: f64,
+    rect: Option<Gd<ColorRect>>,
+
+    base: Base<CanvasLayer>,
+}
+
+#[godot_api]
+impl ScreenFade {
+    #[func]
+    pub fn fade_out(&mut self, duration: f64) {
+        self.target = 1.;
+        self.duration = duration;
+    }
+
+    #[func]
+    pub fn fade_in(&mut self, duration: f64) {
+        self.target = 0.;
+        self.duration = duration;
+    }
+
+    #[func]
+    pub fn is_covered(&self) -> bool {
+        self.target == 1. && self.alpha >= 1.
+    }
+}
+
+#[godot_api]
+impl ICanvasLayer for ScreenFade {
+    fn ready(&mut self) {
+        let mut rect = ColorRect::new_alloc();
+
+        rect.set_anchors_preset(LayoutPreset::FULL_RECT);
+        rect.set_mouse_filter(MouseFilter::IGNORE);
+        rect.set_color(Color::TRANSPARENT_BLACK);
+
+        self.base_mut().add_to_group("fade");
+        self.base_mut().set_layer(100);
+        self.base_mut().add_child(&rect);
+        self.rect = Some(rect);
+    }
+
+    fn process(&mut self, delta: f64) {
+        let step = if self.duration > 0. {
+            delta / self.duration
+        } else {
+            1.
+        };
+
+        self.alpha = move_toward(self.alpha as f64, self.target as f64, step) as f32;
+
+        let mut color = self.color;
+        color.a = self.alpha;
+
+        if let Some(rect) = self.rect.as_mut() {
+            rect.set_color(color);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{audio::audio_manager, menu::GameMenu, save::SaveManager};
+use godot::{
+    classes::{node::ProcessMode, INode, InputEvent},
+    prelude::*,
+};
+
+#[derive(GodotConvert, Var, Export, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[godot(via = i64)]
+pub enum GameMode {
+    #[default]
+    Title,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+// Autoloaded from scene/game_state.tscn. Everything but this node, its menu
+// and the music stops with the tree while the game is not being played.
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+pub struct GameState {
+    // Deaths allowed before the game is over, negative for no limit.
+    #[export]
+    #[init(val = 3)]
+    max_lives: i64,
+    #[var]
+    lives: i64,
+    #[var]
+    mode: GameMode,
+    // Linear bus volumes, 0 to 1. Kept here so they outlive scene reloads,
+    // the audio manager only applies them.
+    #[init(val = 1.)]
+    master_volume: f32,
+    #[init(val = 1.)]
+    music_volume: f32,
+    #[init(val = 1.)]
+    sfx_volume: f32,
+    settings_loaded: bool,
+    menu: Option<Gd<GameMenu>>,
+
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl GameState {
+    #[signal]
+    fn mode_changed(mode: GameMode);
+
+    #[func]
+    pub fn start(&mut self) {
+        if self.mode == GameMode::Title {
+            self.enter(GameMode::Playing);
+        }
+    }
+
+    #[func]
+    pub fn pause(&mut self) {
+        if self.mode == GameMode::Playing {
+            self.enter(GameMode::Paused);
+        }
+    }
+
+    #[func]
+    pub fn resume(&mut self) {
+        if self.mode == GameMode::Paused {
+            self.enter(GameMode::Playing);
+        }
+    }
+
+    #[func]
+    pub fn restart(&mut self) {
+        self.lives = self.max_lives;
+
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.reload_current_scene();
+        }
+
+        self.enter(GameMode::Playing);
+    }
+
+    // `SceneTree::quit` skips the close request the save manager listens for,
+    // so progress and settings are saved here first. Takes `&self` so saving
+    // can still read the volumes from this node.
+    #[func]
+    pub fn quit(&self) {
+        let Some(mut tree) = self.base().get_tree() else {
+            return;
+        };
+
+        let save = tree
+            .get_first_node_in_group("save")
+            .and_then(|save| save.try_cast::<SaveManager>().ok());
+
+        if let Some(mut save) = save {
+            let mut save = save.bind_mut();
+
+            save.save_game();
+            save.save_settings();
+        }
+
+        tree.quit();
+    }
+
+    pub fn player_died(&mut self) {
+        if self.lives < 0 {
+            return;
+        }
+
+        self.lives = (self.lives - 1).max(0);
+
+        if self.lives == 0 {
+            self.enter(GameMode::GameOver);
+        }
+    }
+
+    #[func]
+    pub fn volume(&self, bus: GString) -> f32 {
+        match bus.to_string().as_str() {
+            "Master" => self.master_volume,
+            "Music" => self.music_volume,
+            "SFX" => self.sfx_volume,
+            _ => 0.,
+        }
+    }
+
+    #[func]
+    pub fn set_volume(&mut self, bus: GString, volume: f32) {
+        let volume = volume.clamp(0., 1.);
+
+        match bus.to_string().as_str() {
+            "Master" => self.master_volume = volume,
+            "Music" => self.music_volume = volume,
+            "SFX" => self.sfx_volume = volume,
+            _ => {
+                godot_warn!("Unknown audio bus {bus}.");
+                return;
+            }
+        }
+
+        if let Some(audio) = audio_manager(self.base().get_tree()) {
+            audio.bind().apply_volume(&bus.to_string(), volume);
+        }
+    }
+
+    // Whether the settings file was read this session, so reloaded scenes
+    // keep what was changed since.
+    pub fn settings_loaded(&self) -> bool {
+        self.settings_loaded
+    }
+
+    pub fn mark_settings_loaded(&mut self) {
+        self.settings_loaded = true;
+    }
+
+    fn enter(&mut self, mode: GameMode) {
+        self.mode = mode;
+
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.set_pause(mode != GameMode::Playing);
+        }
+
+        if let Some(menu) = self.menu.as_mut() {
+            menu.bind_mut().open(mode);
+        }
+
+        self.base_mut()
+            .emit_signal("mode_changed", &[mode.to_variant()]);
+    }
+}
+
+#[godot_api]
+impl INode for GameState {
+    fn ready(&mut self) {
+        let mut menu = GameMenu::new_alloc();
+
+        self.lives = self.max_lives;
+        self.base_mut().add_to_group("game_state");
+        self.base_mut().set_process_mode(ProcessMode::ALWAYS);
+        self.base_mut().add_child(&menu);
+
+        menu.bind_mut().attach(&self.to_gd());
+        self.menu = Some(menu);
+
+        let mode = self.mode;
+
+        self.enter(mode);
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        if !event.is_action_pressed("ui_cancel") {
+            return;
+        }
+
+        let settings = self
+            .menu
+            .as_ref()
+            .is_some_and(|menu| menu.bind().settings_open());
+
+        if settings {
+            if let Some(menu) = self.menu.as_mut() {
+                menu.bind_mut().close_settings();
+            }
+        } else if self.mode == GameMode::Playing {
+            self.pause();
+        } else if self.mode == GameMode::Paused {
+            self.resume();
+        }
+    }
+}
+
+pub fn game_state(tree: Option<Gd<SceneTree>>) -> Option<Gd<GameState>> {
+    tree.and_then(|tree| tree.get_first_node_in_group("game_state"))
+        .and_then(|state| state.try_cast::<GameState>().ok())
+}
@@ -1,29 +1,79 @@
+use crate::team::Team;
 use godot::{
-    classes::{Area2D, IArea2D},
+    classes::{Area2D, CollisionShape2D, IArea2D},
     prelude::*,
 };
 
 #[derive(GodotClass)]
 #[class(init, base=Area2D)]
 pub struct Hitbox {
-    default_position: Vector2,
+    // Take team and friendly fire from the owning body.
+    #[export]
+    #[init(val = true)]
+    inherit_team: bool,
+    #[export]
+    team: Team,
+    #[export]
+    friendly_fire: bool,
+
+    #[var]
+    swing: i64,
+    hit: Vec<InstanceId>,
 
     base: Base<Area2D>,
 }
 
 #[godot_api]
 impl Hitbox {
+    // Emitted at most once per body for each swing, including bodies that
+    // were already overlapping when the hitbox was enabled.
+    #[signal]
+    fn struck(body: Gd<Node2D>);
+
+    pub fn inherit(&mut self, team: Team, friendly_fire: bool) {
+        if self.inherit_team {
+            self.team = team;
+            self.friendly_fire = friendly_fire;
+        }
+    }
+
     #[func]
-    fn on_flip(&mut self) {
-        let position = self.base().get_scale();
-        self.base_mut()
-            .set_scale(Vector2::new(position.x * -1., 1.));
+    pub fn begin_swing(&mut self) {
+        self.swing += 1;
+        self.hit.clear();
+    }
+
+    pub fn disarm(&mut self) {
+        for child in self.base().get_children().iter_shared() {
+            if let Ok(mut shape) = child.try_cast::<CollisionShape2D>() {
+                shape.set_disabled(true);
+            }
+        }
+    }
+
+    fn active(&self) -> bool {
+        self.base()
+            .get_children()
+            .iter_shared()
+            .filter_map(|child| child.try_cast::<CollisionShape2D>().ok())
+            .any(|shape| !shape.is_disabled())
     }
 }
 
 #[godot_api]
 impl IArea2D for Hitbox {
-    fn ready(&mut self) {
-        self.default_position = self.base().get_position();
+    fn physics_process(&mut self, _delta: f64) {
+        if !self.active() {
+            return;
+        }
+
+        for body in self.base().get_overlapping_bodies().iter_shared() {
+            if self.hit.contains(&body.instance_id()) {
+                continue;
+            }
+
+            self.hit.push(body.instance_id());
+            self.base_mut().emit_signal("struck", &[body.to_variant()]);
+        }
     }
 }
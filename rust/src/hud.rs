@@ -0,0 +1,283 @@
+use crate::{enemy::Enemy, player::Player};
+use godot::{
+    classes::{Control, Font, IControl, ThemeDB, Timer},
+    global::move_toward,
+    prelude::*,
+};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+const MARGIN: f32 = 24.;
+const BAR_SIZE: Vector2 = Vector2::new(300., 16.);
+const ENERGY_BAR_SIZE: Vector2 = Vector2::new(200., 8.);
+const COOLDOWN_RADIUS: f32 = 20.;
+const COMBO_BAR_SIZE: Vector2 = Vector2::new(60., 4.);
+
+const COOLDOWNS: [(&str, &str); 3] = [
+    ("DashAttackTimer", "A"),
+    ("AuraAttackTimer", "S"),
+    ("FallAttackTimer", "D"),
+];
+
+#[derive(GodotClass)]
+#[class(init, base=Control)]
+pub struct Hud {
+    #[export]
+    #[init(val = 0.4)]
+    trail_delay: f64,
+    #[export]
+    #[init(val = 60.)]
+    trail_speed: f32,
+
+    trail: f32,
+    trail_wait: f64,
+    font: Option<Gd<Font>>,
+
+    base: Base<Control>,
+}
+
+impl Hud {
+    fn player(&self) -> Option<Gd<Player>> {
+        self.base()
+            .get_tree()?
+            .get_first_node_in_group("player")?
+            .try_cast::<Player>()
+            .ok()
+    }
+
+    fn aggro_enemies(&self) -> Vec<Gd<Enemy>> {
+        let Some(tree) = self.base().get_tree() else {
+            return vec![];
+        };
+
+        tree.get_nodes_in_group("enemy")
+            .iter_shared()
+            .filter_map(|enemy| enemy.try_cast::<Enemy>().ok())
+            .filter(|enemy| enemy.bind().get_aggro() && enemy.bind().get_hp() > 0.)
+            .collect()
+    }
+
+    fn draw_bar(&mut self, position: Vector2, size: Vector2, ratio: f32, color: Color) {
+        let ratio = ratio.clamp(0., 1.);
+
+        self.base_mut().draw_rect(
+            Rect2::new(position - Vector2::new(2., 2.), size + Vector2::new(4., 4.)),
+            Color::BLACK,
+        );
+        self.base_mut().draw_rect(
+            Rect2::new(position, Vector2::new(size.x * ratio, size.y)),
+            color,
+        );
+    }
+
+    fn draw_text(&mut self, position: Vector2, text: &str, size: i32) {
+        let Some(font) = self.font.clone() else {
+            return;
+        };
+
+        self.base_mut()
+            .draw_string_ex(&font, position, text)
+            .font_size(size)
+            .done();
+    }
+
+    fn draw_cooldown(&mut self, center: Vector2, timer: Gd<Timer>, label: &str, affordable: bool) {
+        let ratio = if timer.is_stopped() || timer.get_wait_time() <= 0. {
+            0.
+        } else {
+            (timer.get_time_left() / timer.get_wait_time()) as f32
+        };
+
+        self.base_mut()
+            .draw_circle(center, COOLDOWN_RADIUS, Color::from_rgba(0., 0., 0., 0.6));
+
+        if ratio > 0. {
+            self.base_mut()
+                .draw_arc_ex(
+                    center,
+                    COOLDOWN_RADIUS - 3.,
+                    -FRAC_PI_2,
+                    -FRAC_PI_2 + TAU * ratio,
+                    32,
+                    Color::from_rgba(1., 1., 1., 0.5),
+                )
+                .width(6.)
+                .done();
+        } else {
+            self.base_mut()
+                .draw_arc_ex(
+                    center,
+                    COOLDOWN_RADIUS - 1.,
+                    0.,
+                    TAU,
+                    32,
+                    if affordable {
+                        Color::GOLD
+                    } else {
+                        Color::DIM_GRAY
+                    },
+                )
+                .width(2.)
+                .done();
+        }
+
+        self.draw_text(center + Vector2::new(-5., 6.), label, 16);
+    }
+}
+
+#[godot_api]
+impl IControl for Hud {
+    fn ready(&mut self) {
+        self.font = ThemeDB::singleton().get_fallback_font();
+    }
+
+    fn process(&mut self, delta: f64) {
+        let Some(player) = self.player() else {
+            return;
+        };
+
+        let hp = player.bind().get_hp().max(0.);
+
+        if hp >= self.trail {
+            self.trail = hp;
+            self.trail_wait = 0.;
+        } else if self.trail_wait < self.trail_delay {
+            self.trail_wait += delta;
+        } else {
+            self.trail = move_toward(
+                self.trail as f64,
+                hp as f64,
+                (self.trail_speed as f64) * delta,
+            ) as f32;
+        }
+
+        self.base_mut().queue_redraw();
+    }
+
+    fn draw(&mut self) {
+        let Some(player) = self.player() else {
+            return;
+        };
+
+        let hp = player.bind().get_hp().max(0.);
+        let invincible = player.bind().get_invincible();
+        let dashed = player.bind().get_dashed();
+        let energy = player.bind().get_energy();
+        let max_energy = player.bind().get_max_energy();
+        let combo = player.bind().get_combo();
+        let combo_time = player.bind().get_combo_time();
+        let combo_timeout = player.bind().get_combo_timeout();
+
+        let position = Vector2::new(MARGIN, MARGIN);
+
+        self.draw_bar(position, BAR_SIZE, self.trail / 100., Color::WHITE);
+        self.base_mut().draw_rect(
+            Rect2::new(position, Vector2::new(BAR_SIZE.x * hp / 100., BAR_SIZE.y)),
+            if invincible {
+                Color::LIGHT_GRAY
+            } else {
+                Color::GREEN
+            },
+        );
+
+        let energy_position = position + Vector2::new(0., BAR_SIZE.y + 8.);
+
+        self.draw_bar(
+            energy_position,
+            ENERGY_BAR_SIZE,
+            if max_energy > 0. {
+                energy / max_energy
+            } else {
+                0.
+            },
+            Color::GOLDENROD,
+        );
+
+        let mut center = energy_position
+            + Vector2::new(COOLDOWN_RADIUS, ENERGY_BAR_SIZE.y + 16. + COOLDOWN_RADIUS);
+
+        for (name, label) in COOLDOWNS {
+            let affordable = energy >= player.bind().skill_cost(name);
+
+            self.draw_cooldown(center, player.get_node_as::<Timer>(name), label, affordable);
+
+            center.x += COOLDOWN_RADIUS * 2. + 12.;
+        }
+
+        self.base_mut().draw_circle(
+            center,
+            8.,
+            if dashed {
+                Color::from_rgba(1., 1., 1., 0.2)
+            } else {
+                Color::SKY_BLUE
+            },
+        );
+
+        // A single hit is not a combo yet.
+        if combo > 1 {
+            let position = center + Vector2::new(8. + 16., 0.);
+
+            self.draw_text(
+                position + Vector2::new(0., 6.),
+                &format!("{combo} hits"),
+                18,
+            );
+            self.draw_bar(
+                position + Vector2::new(0., 14.),
+                COMBO_BAR_SIZE,
+                if combo_timeout > 0. {
+                    (combo_time / combo_timeout) as f32
+                } else {
+                    0.
+                },
+                Color::ORANGE,
+            );
+        }
+
+        let enemies = self.aggro_enemies();
+        let bosses: Vec<_> = enemies
+            .iter()
+            .filter(|enemy| enemy.bind().get_boss())
+            .cloned()
+            .collect();
+
+        let size = self.base().get_size();
+        let width = size.x * 0.5;
+        let mut bottom = size.y - MARGIN - BAR_SIZE.y;
+
+        if bosses.is_empty() {
+            if !enemies.is_empty() {
+                let total: f32 = enemies
+                    .iter()
+                    .map(|enemy| enemy.bind().get_hp().max(0.))
+                    .sum();
+                let ratio = total / (enemies.len() as f32 * 100.);
+
+                self.draw_bar(
+                    Vector2::new((size.x - width) / 2., bottom),
+                    Vector2::new(width, BAR_SIZE.y / 2.),
+                    ratio,
+                    Color::INDIAN_RED,
+                );
+            }
+
+            return;
+        }
+
+        for boss in bosses {
+            let name = boss.bind().get_display_name().to_string();
+            let ratio = boss.bind().get_hp() / 100.;
+            let left = (size.x - width) / 2.;
+
+            self.draw_bar(
+                Vector2::new(left, bottom),
+                Vector2::new(width, BAR_SIZE.y),
+                ratio,
+                Color::RED,
+            );
+            self.draw_text(Vector2::new(left, bottom - 6.), &name, 18);
+
+            bottom -= BAR_SIZE.y + 30.;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::physics::damp;
+use godot::{classes::CharacterBody2D, prelude::*};
+
+#[derive(Default)]
+pub struct Knockback {
+    pending: Option<Vector2>,
+    // Upward speed held until the body has actually left the floor.
+    lift: Option<f32>,
+    hitstun: f64,
+    juggles: u32,
+    airborne: bool,
+    bounced: bool,
+}
+
+impl Knockback {
+    // Heavier bodies fly less far. Past the juggle limit, hits in the air no
+    // longer lift the body any further.
+    pub fn launch(&mut self, impulse: Vector2, hitstun: f64, weight: f32, max_juggles: u32) {
+        let mut impulse = impulse / weight.max(0.1);
+
+        if self.airborne {
+            self.juggles += 1;
+
+            if self.juggles > max_juggles {
+                impulse.y = impulse.y.max(0.);
+            }
+        }
+
+        self.pending = Some(impulse);
+        self.hitstun = self.hitstun.max(hitstun);
+        self.bounced = false;
+    }
+
+    pub fn active(&self) -> bool {
+        self.pending.is_some() || self.hitstun > 0. || self.airborne
+    }
+
+    pub fn stunned(&self) -> bool {
+        self.pending.is_some() || self.hitstun > 0.
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    // Takes over the velocity while launched and returns whether it did.
+    pub fn update(
+        &mut self,
+        body: &Gd<CharacterBody2D>,
+        velocity: &mut Vector2,
+        wall_bounce: f32,
+        delta: f64,
+    ) -> bool {
+        if let Some(impulse) = self.pending.take() {
+            *velocity = impulse;
+
+            self.lift = (impulse.y < 0.).then_some(impulse.y);
+            self.airborne = self.lift.is_some() || !body.is_on_floor();
+
+            return true;
+        }
+
+        if !self.active() {
+            return false;
+        }
+
+        self.hitstun = (self.hitstun - delta).max(0.);
+
+        let on_floor = body.is_on_floor();
+
+        match self.lift {
+            Some(lift) if on_floor => velocity.y = lift,
+            _ => self.lift = None,
+        }
+
+        if body.is_on_wall() && !self.bounced && velocity.x * body.get_wall_normal().x < 0. {
+            velocity.x *= -wall_bounce;
+            self.bounced = true;
+        }
+
+        if on_floor && self.lift.is_none() {
+            self.airborne = false;
+            self.juggles = 0;
+
+            velocity.x = damp(velocity.x, 0., 0.2, delta);
+        }
+
+        self.active()
+    }
+}
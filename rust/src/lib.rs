@@ -7,15 +7,34 @@ use godot::{classes::Time, init::EditorRunBehavior, prelude::*};
 //         - Put out some conditions from if statements using variable as category.
 // TODO: Add a boss from enemy resources.
 
+mod ability;
 mod area;
+mod attack;
+mod audio;
 mod aura;
 mod camera;
-mod collider;
+mod checkpoint;
+mod combat;
 mod crack;
+mod damage;
+mod effect;
 mod enemy;
+mod facing;
+mod fade;
+mod game;
 mod hitbox;
+mod hud;
+mod knockback;
 mod map;
+mod marker;
+mod menu;
+mod physics;
+mod platform;
 mod player;
+mod pool;
+mod save;
+mod status;
+mod team;
 
 struct PrismRunner;
 
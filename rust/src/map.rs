@@ -1,26 +1,171 @@
-use crate::camera::SideCamera;
-use godot::prelude::*;
+use godot::{
+    classes::{Camera2D, Resource, Texture2D},
+    prelude::*,
+};
+use std::f64::consts::TAU;
+
+#[derive(GodotClass)]
+#[class(init, base=Resource)]
+pub struct BackdropLayer {
+    #[export]
+    texture: Option<Gd<Texture2D>>,
+    #[export]
+    #[init(val = Vector2::new(1., 1.))]
+    texture_scale: Vector2,
+    // 0 keeps the layer fixed to the screen, 1 moves it with the world.
+    #[export]
+    #[init(val = Vector2::new(1., 1.))]
+    scroll: Vector2,
+    #[export]
+    offset: Vector2,
+    // Non-positive height fills the whole view.
+    #[export]
+    height: f32,
+    #[export]
+    #[init(val = Color::WHITE)]
+    top_color: Color,
+    #[export]
+    #[init(val = Color::WHITE)]
+    bottom_color: Color,
+    #[export]
+    #[init(val = true)]
+    day_night: bool,
+
+    base: Base<Resource>,
+}
+
+impl BackdropLayer {
+    fn draw(&self, canvas: &mut Gd<Node2D>, view: Rect2, tint: Color) {
+        let tint = if self.day_night { tint } else { Color::WHITE };
+        let origin = canvas.get_global_position();
+
+        let (top, height) = if self.height > 0. {
+            (
+                self.offset.y + view.position.y * (1. - self.scroll.y),
+                self.height,
+            )
+        } else {
+            (view.position.y, view.size.y)
+        };
+
+        let left = view.position.x - origin.x;
+        let right = left + view.size.x;
+        let top = top - origin.y;
+        let bottom = top + height;
+
+        let points = PackedVector2Array::from(&[
+            Vector2::new(left, top),
+            Vector2::new(right, top),
+            Vector2::new(right, bottom),
+            Vector2::new(left, bottom),
+        ]);
+        let top_color = multiply(self.top_color, tint);
+        let bottom_color = multiply(self.bottom_color, tint);
+        let colors = PackedColorArray::from(&[top_color, top_color, bottom_color, bottom_color]);
+
+        canvas.draw_polygon(&points, &colors);
+
+        let Some(texture) = self.texture.as_ref() else {
+            return;
+        };
+
+        let size = texture.get_size() * self.texture_scale;
+
+        if size.x <= 0. || size.y <= 0. {
+            return;
+        }
+
+        let anchor = view.position.x * (1. - self.scroll.x) + self.offset.x - origin.x;
+        let mut x = anchor + ((left - anchor) / size.x).floor() * size.x;
+
+        while x < right {
+            canvas
+                .draw_texture_rect_ex(texture, Rect2::new(Vector2::new(x, top), size), false)
+                .modulate(tint)
+                .done();
+
+            x += size.x;
+        }
+    }
+}
 
 #[derive(GodotClass)]
 #[class(init, base=Node2D)]
 struct Map {
+    #[export]
+    layers: Array<Gd<BackdropLayer>>,
+    // Length of a full day/night cycle in seconds, 0 freezes the current time.
+    #[export]
+    day_length: f64,
+    #[export]
+    #[init(val = Color::WHITE)]
+    day_tint: Color,
+    #[export]
+    #[init(val = Color::from_rgb(0.35, 0.35, 0.55))]
+    night_tint: Color,
+    // 0 is noon, 0.5 is midnight.
+    #[var]
+    time_of_day: f64,
+
+    view: Rect2,
+
     base: Base<Node2D>,
 }
 
+impl Map {
+    fn camera(&self) -> Option<Gd<Camera2D>> {
+        self.base().get_viewport()?.get_camera_2d()
+    }
+
+    fn tint(&self) -> Color {
+        let night = ((1. - (self.time_of_day * TAU).cos()) / 2.) as f32;
+
+        mix(self.day_tint, self.night_tint, night)
+    }
+}
+
 #[godot_api]
 impl INode2D for Map {
     fn draw(&mut self) {
-        self.base_mut().draw_rect(
-            Rect2::new(Vector2::ZERO, Vector2::new(2000., 500.)),
-            Color::from_html("#170f20").unwrap(),
-        );
+        let view = self.view;
+        let tint = self.tint();
+        let layers = self.layers.clone();
+
+        for layer in layers.iter_shared() {
+            layer.bind().draw(&mut self.base_mut(), view, tint);
+        }
     }
 
-    fn physics_process(&mut self, _delta: f64) {
-        let parent = self.base_mut().get_parent().unwrap();
-        let camera: Gd<SideCamera> = parent.get_node_as("SideCamera");
+    fn process(&mut self, delta: f64) {
+        if self.day_length > 0. {
+            self.time_of_day = (self.time_of_day + delta / self.day_length).fract();
+        }
+
+        let Some(camera) = self.camera() else {
+            return;
+        };
+
+        let size = self.base().get_viewport_rect().size / camera.get_zoom();
 
-        self.base_mut()
-            .set_position(Vector2::new(camera.get_position().x - 1000., 325.));
+        self.view = Rect2::new(camera.get_screen_center_position() - size / 2., size);
+        self.base_mut().queue_redraw();
     }
 }
+
+fn mix(from: Color, to: Color, weight: f32) -> Color {
+    Color::from_rgba(
+        from.r + (to.r - from.r) * weight,
+        from.g + (to.g - from.g) * weight,
+        from.b + (to.b - from.b) * weight,
+        from.a + (to.a - from.a) * weight,
+    )
+}
+
+fn multiply(color: Color, tint: Color) -> Color {
+    Color::from_rgba(
+        color.r * tint.r,
+        color.g * tint.g,
+        color.b * tint.b,
+        color.a * tint.a,
+    )
+}
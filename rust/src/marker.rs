@@ -0,0 +1,139 @@
+pub struct Marker {
+    pub animation: &'static str,
+    pub frame: i32,
+    // A name with an optional argument, as in "shake:30".
+    pub event: &'static str,
+}
+
+pub const fn marker(animation: &'static str, frame: i32, event: &'static str) -> Marker {
+    Marker {
+        animation,
+        frame,
+        event,
+    }
+}
+
+pub fn parse(event: &str) -> (&str, Option<&str>) {
+    match event.split_once(':') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (event, None),
+    }
+}
+
+#[derive(Default)]
+pub struct AnimationEvents {
+    animation: String,
+    frame: i32,
+}
+
+impl AnimationEvents {
+    // Reports whether the animation started over since the last update, and
+    // every marker reached since then, including frames that were skipped.
+    pub fn update(
+        &mut self,
+        markers: &[Marker],
+        animation: &str,
+        frame: i32,
+    ) -> (bool, Vec<&'static str>) {
+        let started = animation != self.animation || frame < self.frame;
+
+        if !started && frame == self.frame {
+            return (false, Vec::new());
+        }
+
+        let from = if started { -1 } else { self.frame };
+
+        self.animation = animation.to_string();
+        self.frame = frame;
+
+        let events = markers
+            .iter()
+            .filter(|marker| marker.animation == animation)
+            .filter(|marker| marker.frame > from && marker.frame <= frame)
+            .map(|marker| marker.event)
+            .collect();
+
+        (started, events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKERS: [Marker; 5] = [
+        marker("attack", 0, "swing"),
+        marker("attack", 2, "hitbox:Slash"),
+        marker("attack", 4, "hitbox_off"),
+        marker("attack", 4, "shake:30"),
+        marker("run", 1, "footstep"),
+    ];
+
+    fn play(events: &mut AnimationEvents, animation: &str, frames: &[i32]) -> Vec<&'static str> {
+        frames
+            .iter()
+            .flat_map(|&frame| events.update(&MARKERS, animation, frame).1)
+            .collect()
+    }
+
+    #[test]
+    fn fires_each_marker_once() {
+        let mut events = AnimationEvents::default();
+        let fired = play(&mut events, "attack", &[0, 0, 1, 1, 2, 2, 3, 4, 4, 5]);
+
+        assert_eq!(fired, ["swing", "hitbox:Slash", "hitbox_off", "shake:30"]);
+    }
+
+    #[test]
+    fn catches_skipped_frames() {
+        let mut events = AnimationEvents::default();
+
+        assert_eq!(play(&mut events, "attack", &[0, 1]), ["swing"]);
+        assert_eq!(play(&mut events, "attack", &[3]), ["hitbox:Slash"]);
+        assert_eq!(
+            play(&mut events, "attack", &[6]),
+            ["hitbox_off", "shake:30"]
+        );
+    }
+
+    #[test]
+    fn restarts_when_looping() {
+        let mut events = AnimationEvents::default();
+
+        assert_eq!(events.update(&MARKERS, "run", 0), (true, Vec::new()));
+        assert_eq!(events.update(&MARKERS, "run", 1), (false, vec!["footstep"]));
+        assert_eq!(events.update(&MARKERS, "run", 3), (false, Vec::new()));
+        assert_eq!(events.update(&MARKERS, "run", 0), (true, Vec::new()));
+        assert_eq!(events.update(&MARKERS, "run", 1), (false, vec!["footstep"]));
+    }
+
+    #[test]
+    fn restarts_when_switching_animations() {
+        let mut events = AnimationEvents::default();
+
+        assert_eq!(play(&mut events, "run", &[0, 1, 2]), ["footstep"]);
+        assert_eq!(
+            events.update(&MARKERS, "attack", 2),
+            (true, vec!["swing", "hitbox:Slash"])
+        );
+        assert_eq!(events.update(&MARKERS, "run", 2), (true, vec!["footstep"]));
+    }
+
+    #[test]
+    fn fires_every_marker_on_a_frame() {
+        let mut events = AnimationEvents::default();
+
+        play(&mut events, "attack", &[0, 3]);
+
+        assert_eq!(
+            events.update(&MARKERS, "attack", 4),
+            (false, vec!["hitbox_off", "shake:30"])
+        );
+    }
+
+    #[test]
+    fn parses_arguments() {
+        assert_eq!(parse("shake:30"), ("shake", Some("30")));
+        assert_eq!(parse("swing"), ("swing", None));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::game::{game_state, GameMode, GameState};
+use godot::{
+    classes::{
+        control::{LayoutPreset, MouseFilter},
+        Button, CanvasLayer, CenterContainer, ColorRect, Control, HSlider, ICanvasLayer, Label,
+        VBoxContainer,
+    },
+    prelude::*,
+};
+
+const BUTTON_WIDTH: f32 = 220.;
+const VOLUMES: [(&str, &str, &str); 3] = [
+    ("Master", "Master volume", "on_master_changed"),
+    ("Music", "Music volume", "on_music_changed"),
+    ("SFX", "Effects volume", "on_sfx_changed"),
+];
+
+struct Buttons {
+    start: Gd<Button>,
+    resume: Gd<Button>,
+    restart: Gd<Button>,
+    settings: Gd<Button>,
+    quit: Gd<Button>,
+}
+
+// Built in code so the whole menu lives with `GameState` and needs no scene.
+#[derive(GodotClass)]
+#[class(init, base=CanvasLayer)]
+pub struct GameMenu {
+    title: Option<Gd<Label>>,
+    main: Option<Gd<VBoxContainer>>,
+    settings: Option<Gd<VBoxContainer>>,
+    sliders: Vec<Gd<HSlider>>,
+    buttons: Option<Buttons>,
+
+    base: Base<CanvasLayer>,
+}
+
+#[godot_api]
+impl GameMenu {
+    pub fn attach(&mut self, state: &Gd<GameState>) {
+        let Some(buttons) = self.buttons.as_mut() else {
+            return;
+        };
+
+        for (button, method) in [
+            (&mut buttons.start, "start"),
+            (&mut buttons.resume, "resume"),
+            (&mut buttons.restart, "restart"),
+            (&mut buttons.quit, "quit"),
+        ] {
+            button.connect("pressed", &Callable::from_object_method(state, method));
+        }
+    }
+
+    pub fn open(&mut self, mode: GameMode) {
+        let title = match mode {
+            GameMode::Title => "Prism Runner",
+            GameMode::Playing => "",
+            GameMode::Paused => "Paused",
+            GameMode::GameOver => "Game Over",
+        };
+
+        if let Some(label) = self.title.as_mut() {
+            label.set_text(title);
+        }
+
+        if let Some(buttons) = self.buttons.as_mut() {
+            buttons.start.set_visible(mode == GameMode::Title);
+            buttons.resume.set_visible(mode == GameMode::Paused);
+            buttons
+                .restart
+                .set_visible(matches!(mode, GameMode::Paused | GameMode::GameOver));
+            buttons
+                .settings
+                .set_visible(matches!(mode, GameMode::Title | GameMode::Paused));
+        }
+
+        self.show_settings(false);
+        self.base_mut().set_visible(mode != GameMode::Playing);
+
+        if mode == GameMode::Playing {
+            return;
+        }
+
+        let first = self.buttons.as_ref().and_then(|buttons| {
+            [&buttons.start, &buttons.resume, &buttons.restart]
+                .into_iter()
+                .find(|button| button.is_visible())
+                .cloned()
+        });
+
+        if let Some(button) = first {
+            focus(button.upcast());
+        }
+    }
+
+    pub fn settings_open(&self) -> bool {
+        self.settings
+            .as_ref()
+            .is_some_and(|panel| panel.is_visible())
+    }
+
+    #[func]
+    fn open_settings(&mut self) {
+        if let Some(state) = game_state(self.base().get_tree()) {
+            for (&(bus, _, _), slider) in VOLUMES.iter().zip(self.sliders.iter_mut()) {
+                let volume = state.bind().volume(bus.into());
+
+                slider.set_value_no_signal(volume as f64);
+            }
+        }
+
+        self.show_settings(true);
+
+        if let Some(slider) = self.sliders.first() {
+            focus(slider.clone().upcast());
+        }
+    }
+
+    #[func]
+    pub fn close_settings(&mut self) {
+        if !self.settings_open() {
+            return;
+        }
+
+        self.show_settings(false);
+
+        if let Some(buttons) = self.buttons.as_ref() {
+            focus(buttons.settings.clone().upcast());
+        }
+    }
+
+    fn show_settings(&mut self, visible: bool) {
+        if let Some(main) = self.main.as_mut() {
+            main.set_visible(!visible);
+        }
+
+        if let Some(settings) = self.settings.as_mut() {
+            settings.set_visible(visible);
+        }
+    }
+
+    fn set_volume(&mut self, bus: &str, volume: f64) {
+        if let Some(mut state) = game_state(self.base().get_tree()) {
+            state.bind_mut().set_volume(bus.into(), volume as f32);
+        }
+    }
+
+    #[func]
+    fn on_master_changed(&mut self, volume: f64) {
+        self.set_volume("Master", volume);
+    }
+
+    #[func]
+    fn on_music_changed(&mut self, volume: f64) {
+        self.set_volume("Music", volume);
+    }
+
+    #[func]
+    fn on_sfx_changed(&mut self, volume: f64) {
+        self.set_volume("SFX", volume);
+    }
+
+    fn button(&self, panel: &mut Gd<VBoxContainer>, text: &str) -> Gd<Button> {
+        let mut button = Button::new_alloc();
+
+        button.set_text(text);
+        button.set_custom_minimum_size(Vector2::new(BUTTON_WIDTH, 0.));
+        panel.add_child(&button);
+
+        button
+    }
+}
+
+// Deferred so the control is already visible when it takes focus, which
+// keyboard and gamepad navigation need to start from.
+fn focus(mut control: Gd<Control>) {
+    control.call_deferred("grab_focus", &[]);
+}
+
+#[godot_api]
+impl ICanvasLayer for GameMenu {
+    fn ready(&mut self) {
+        let this = self.to_gd();
+
+        let mut dim = ColorRect::new_alloc();
+        let mut center = CenterContainer::new_alloc();
+        let mut main = VBoxContainer::new_alloc();
+        let mut settings = VBoxContainer::new_alloc();
+        let title = Label::new_alloc();
+
+        dim.set_anchors_preset(LayoutPreset::FULL_RECT);
+        dim.set_mouse_filter(MouseFilter::IGNORE);
+        dim.set_color(Color::from_rgba(0., 0., 0., 0.6));
+        center.set_anchors_preset(LayoutPreset::FULL_RECT);
+
+        main.add_child(&title);
+
+        let start = self.button(&mut main, "Start");
+        let resume = self.button(&mut main, "Resume");
+        let restart = self.button(&mut main, "Restart");
+        let mut settings_button = self.button(&mut main, "Settings");
+        let quit = self.button(&mut main, "Quit");
+
+        settings_button.connect(
+            "pressed",
+            &Callable::from_object_method(&this, "open_settings"),
+        );
+
+        let mut heading = Label::new_alloc();
+
+        heading.set_text("Settings");
+        settings.add_child(&heading);
+
+        for (_, text, method) in VOLUMES {
+            let mut label = Label::new_alloc();
+            let mut slider = HSlider::new_alloc();
+
+            label.set_text(text);
+            slider.set_min(0.);
+            slider.set_max(1.);
+            slider.set_step(0.05);
+            slider.set_value_no_signal(1.);
+            slider.set_custom_minimum_size(Vector2::new(BUTTON_WIDTH, 0.));
+            slider.connect(
+                "value_changed",
+                &Callable::from_object_method(&this, method),
+            );
+            settings.add_child(&label);
+            settings.add_child(&slider);
+            self.sliders.push(slider);
+        }
+
+        let mut back = self.button(&mut settings, "Back");
+
+        back.connect(
+            "pressed",
+            &Callable::from_object_method(&this, "close_settings"),
+        );
+        settings.set_visible(false);
+
+        center.add_child(&main);
+        center.add_child(&settings);
+
+        self.base_mut().set_layer(90);
+        self.base_mut().add_child(&dim);
+        self.base_mut().add_child(&center);
+
+        self.title = Some(title);
+        self.main = Some(main);
+        self.settings = Some(settings);
+        self.buttons = Some(Buttons {
+            start,
+            resume,
+            restart,
+            settings: settings_button,
+            quit,
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use godot::{classes::ProjectSettings, prelude::*};
+
+// Tuning values were originally authored as per-tick steps at this rate.
+pub const REFERENCE_RATE: f32 = 60.;
+
+pub fn gravity() -> f32 {
+    ProjectSettings::singleton()
+        .get_setting("physics/2d/default_gravity")
+        .to::<f32>()
+        / 35.
+        * REFERENCE_RATE
+}
+
+// Equivalent of `value.lerp(target, weight)` once per reference tick, for any `delta`.
+pub fn damp(value: f32, target: f32, weight: f32, delta: f64) -> f32 {
+    let t = 1. - (1. - weight).powf(REFERENCE_RATE * delta as f32);
+
+    value + (target - value) * t
+}
+
+pub fn damp_vector(value: Vector2, target: Vector2, weight: f32, delta: f64) -> Vector2 {
+    Vector2::new(
+        damp(value.x, target.x, weight, delta),
+        damp(value.y, target.y, weight, delta),
+    )
+}
+
+// Vertical velocity after accelerating for `delta`, capped at `max_speed`.
+pub fn fall(velocity: f32, acceleration: f32, max_speed: f32, delta: f64) -> f32 {
+    (velocity + acceleration * delta as f32).min(max_speed)
+}
+
+// Raises `value` by `rate` per second, up to `max`.
+pub fn regen(value: f32, rate: f32, max: f32, delta: f64) -> f32 {
+    (value + rate * delta as f32).min(max)
+}
+
+// Lowers `value` by `rate` per second, down to 0.
+pub fn decay(value: f32, rate: f32, delta: f64) -> f32 {
+    (value - rate * delta as f32).max(0.)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub const RATES: [f64; 3] = [30., 60., 120.];
+    pub const EPSILON: f32 = 1e-3;
+
+    // Runs `step` for one second at `rate` and returns what it accumulated.
+    pub fn run<T>(rate: f64, mut value: T, mut step: impl FnMut(T, f64) -> T) -> T {
+        for _ in 0..rate as usize {
+            value = step(value, 1. / rate);
+        }
+
+        value
+    }
+
+    pub fn assert_rate_independent(step: impl Fn(f32, f64) -> f32 + Copy, start: f32) {
+        let expected = run(REFERENCE_RATE as f64, start, step);
+
+        for rate in RATES {
+            let value = run(rate, start, step);
+
+            assert!(
+                (value - expected).abs() <= EPSILON * expected.abs().max(1.),
+                "{value} at {rate} Hz, expected {expected}",
+            );
+        }
+    }
+
+    #[test]
+    fn damp_matches_reference_ticks() {
+        let expected =
+            (0..REFERENCE_RATE as usize).fold(100_f32, |value, _| value + (0. - value) * 0.1);
+
+        for rate in RATES {
+            let value = run(rate, 100., |value, delta| damp(value, 0., 0.1, delta));
+
+            assert!((value - expected).abs() < EPSILON, "{value} at {rate} Hz");
+        }
+    }
+
+    #[test]
+    fn damp_vector_matches_reference_ticks() {
+        let start = Vector2::new(100., -40.);
+        let target = Vector2::new(-20., 60.);
+        let step = |value, delta| damp_vector(value, target, 0.2, delta);
+        let expected = run(REFERENCE_RATE as f64, start, step);
+
+        for rate in RATES {
+            let value = run(rate, start, step);
+
+            assert!(
+                (value - expected).length() < EPSILON,
+                "{value} at {rate} Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn fall_matches_reference_ticks() {
+        assert_rate_independent(|velocity, delta| fall(velocity, 1680., 750., delta), -400.);
+        assert_rate_independent(|velocity, delta| fall(velocity, 1680., 750., delta), 0.);
+        assert_eq!(
+            run(30., 0., |velocity, delta| fall(
+                velocity, 1680., 750., delta
+            )),
+            750.
+        );
+    }
+
+    #[test]
+    fn regen_matches_reference_ticks() {
+        assert_rate_independent(|hp, delta| regen(hp, 3., 100., delta), 40.);
+        assert_eq!(run(120., 99., |hp, delta| regen(hp, 3., 100., delta)), 100.);
+    }
+
+    #[test]
+    fn decay_matches_reference_ticks() {
+        assert_rate_independent(|shake, delta| decay(shake, 180., delta), 200.);
+        assert_eq!(run(30., 100., |shake, delta| decay(shake, 180., delta)), 0.);
+    }
+}
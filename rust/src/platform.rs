@@ -0,0 +1,87 @@
+use godot::{
+    classes::{CharacterBody2D, CollisionPolygon2D, CollisionShape2D, IStaticBody2D, StaticBody2D},
+    prelude::*,
+};
+
+pub const PLATFORM_LAYER: i32 = 2;
+
+const DROP_TIME: f64 = 0.3;
+
+#[derive(GodotClass)]
+#[class(init, base=StaticBody2D)]
+pub struct OneWayPlatform {
+    #[export]
+    #[init(val = 4.)]
+    margin: f32,
+
+    base: Base<StaticBody2D>,
+}
+
+#[godot_api]
+impl IStaticBody2D for OneWayPlatform {
+    fn ready(&mut self) {
+        let margin = self.margin;
+
+        self.base_mut().set_collision_layer(0);
+        self.base_mut()
+            .set_collision_layer_value(PLATFORM_LAYER, true);
+        self.base_mut().set_collision_mask(0);
+        self.base_mut().add_to_group("platform");
+
+        for child in self.base().get_children().iter_shared() {
+            if let Ok(mut shape) = child.clone().try_cast::<CollisionShape2D>() {
+                shape.set_one_way_collision(true);
+                shape.set_one_way_collision_margin(margin);
+            } else if let Ok(mut polygon) = child.try_cast::<CollisionPolygon2D>() {
+                polygon.set_one_way_collision(true);
+                polygon.set_one_way_collision_margin(margin);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DropThrough {
+    time: f64,
+}
+
+impl DropThrough {
+    pub fn attach(&mut self, body: &mut Gd<CharacterBody2D>) {
+        body.set_collision_mask_value(PLATFORM_LAYER, true);
+    }
+
+    pub fn on_platform(&self, body: &Gd<CharacterBody2D>) -> bool {
+        if !body.is_on_floor() {
+            return false;
+        }
+
+        (0..body.get_slide_collision_count()).any(|index| {
+            body.get_slide_collision(index)
+                .filter(|collision| collision.get_normal().y < 0.)
+                .and_then(|collision| collision.get_collider())
+                .is_some_and(|collider| collider.try_cast::<OneWayPlatform>().is_ok())
+        })
+    }
+
+    pub fn dropping(&self) -> bool {
+        self.time > 0.
+    }
+
+    pub fn start(&mut self, body: &mut Gd<CharacterBody2D>) {
+        self.time = DROP_TIME;
+
+        body.set_collision_mask_value(PLATFORM_LAYER, false);
+    }
+
+    pub fn update(&mut self, body: &mut Gd<CharacterBody2D>, delta: f64) {
+        if self.time <= 0. {
+            return;
+        }
+
+        self.time -= delta;
+
+        if self.time <= 0. {
+            body.set_collision_mask_value(PLATFORM_LAYER, true);
+        }
+    }
+}
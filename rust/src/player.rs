@@ -1,15 +1,60 @@
 use crate::{
-    aura::SwordAura, camera::SideCamera, crack::GroundCrack, enemy::Enemy, hitbox::Hitbox,
+    ability::{Abilities, Ability, MAX_TIER},
+    area::{ClimbKind, ClimbSpot},
+    attack::{Attack, BASIC_ATTACK, EARTHQUAKE, FALL_ATTACK, STRONG_ATTACK},
+    audio::{play_sfx, Sfx},
+    aura::SwordAura,
+    camera::SideCamera,
+    combat::{land, Attacker, Modifiers},
+    crack::GroundCrack,
+    damage::{show_damage, DamageKind, Flash},
+    enemy::Enemy,
+    facing::Facing,
+    fade::ScreenFade,
+    game::game_state,
+    hitbox::Hitbox,
+    knockback::Knockback,
+    marker::{marker, parse, AnimationEvents, Marker},
+    physics::{self, damp, fall, regen},
+    platform::DropThrough,
+    pool::EffectPool,
+    save::{read, read_vector, vector},
+    status::{StatusEffect, StatusEffects},
+    team::Team,
 };
 use godot::{
     classes::{
-        AnimatedSprite2D, CharacterBody2D, CollisionShape2D, ICharacterBody2D, InputEvent,
-        ProjectSettings, Timer,
+        AnimatedSprite2D, CharacterBody2D, CollisionShape2D, Curve, ICharacterBody2D, InputEvent,
+        PhysicsDirectSpaceState2D, PhysicsRayQueryParameters2D, Timer,
     },
     global::{move_toward, Key},
     prelude::*,
 };
 
+const HITBOXES: [&str; 4] = ["BasicAttack", "StrongAttack", "FallAttack", "Earthquake"];
+const SWINGS: [&str; 4] = ["basic_attack", "dash_attack", "aura_attack", "fall_attack"];
+const EMPOWER: StatusEffect = StatusEffect::empower(5., 1.25);
+const MARKERS: &[Marker] = &[
+    marker("slide", 0, "invincible"),
+    marker("slide", 6, "vulnerable"),
+    marker("basic_attack", 5, "hitbox:BasicAttack/Collision"),
+    marker("basic_attack", 7, "hitbox_off"),
+    marker("basic_attack", 9, "hitbox:BasicAttack/Collision"),
+    marker("basic_attack", 11, "hitbox_off"),
+    marker("aura_attack", 3, "hitbox:StrongAttack/Collision"),
+    marker("aura_attack", 3, "shake:30"),
+    marker("aura_attack", 3, "spawn_aura"),
+    marker("aura_attack", 5, "hitbox_off"),
+    marker("dash_attack_finished", 3, "hitbox:StrongAttack/Collision"),
+    marker("dash_attack_finished", 3, "shake:30"),
+    marker("dash_attack_finished", 5, "hitbox_off"),
+    marker("fall_attack", 0, "hitbox:FallAttack/Collision"),
+    marker("fall_attack_finished", 1, "hitbox:Earthquake/Collision"),
+    marker("fall_attack_finished", 2, "hitbox_off"),
+    marker("run", 2, "footstep"),
+    marker("run", 6, "footstep"),
+];
+
 #[derive(GodotClass)]
 #[class(init, base=CharacterBody2D)]
 pub struct Player {
@@ -20,13 +65,151 @@ pub struct Player {
     speed: f32,
     #[init(val = 600.)]
     jump_power: f32,
+    // Upward velocity is multiplied by this when jump is released early.
+    #[export]
+    #[init(val = 0.5)]
+    jump_cut: f32,
+    #[export]
+    double_jump: bool,
+    #[export]
+    #[init(val = 520.)]
+    double_jump_power: f32,
+    #[export]
+    #[init(val = 4000.)]
+    ground_acceleration: f32,
+    #[export]
+    #[init(val = 6000.)]
+    ground_deceleration: f32,
+    #[export]
+    #[init(val = 2000.)]
+    air_acceleration: f32,
+    #[export]
+    #[init(val = 1000.)]
+    air_deceleration: f32,
+    // Sampled with the current speed ratio (0..1) to scale the rates above.
+    #[export]
+    acceleration_curve: Option<Gd<Curve>>,
+    #[export]
+    deceleration_curve: Option<Gd<Curve>>,
+    #[export]
+    #[init(val = 6.)]
+    hp_regen: f32,
+    #[export]
+    #[init(val = 18000.)]
+    fall_attack_acceleration: f32,
+    #[var]
+    #[init(val = 100.)]
+    energy: f32,
+    #[export]
+    #[init(val = 100.)]
+    max_energy: f32,
+    #[export]
+    #[init(val = 5.)]
+    energy_regen: f32,
+    #[export]
+    #[init(val = 8.)]
+    energy_per_hit: f32,
+    // Spent alongside the DashAttackTimer/AuraAttackTimer/FallAttackTimer cooldowns.
+    #[export]
+    #[init(val = 30.)]
+    dash_attack_cost: f32,
+    #[export]
+    #[init(val = 40.)]
+    aura_attack_cost: f32,
+    #[export]
+    #[init(val = 25.)]
+    fall_attack_cost: f32,
+    // Names from the ability registry that start locked, e.g. "dash_attack".
+    #[export]
+    locked_abilities: PackedStringArray,
+    #[export]
+    #[init(val = 0.5)]
+    aura_reach_per_tier: f32,
+    #[export]
+    #[init(val = 0.25)]
+    earthquake_radius_per_tier: f32,
+    #[export]
+    #[init(val = 0.2)]
+    fall_attack_cooldown_per_tier: f64,
     // TODO: Implement resistance mechanism for player.
     #[var]
     resistance: bool,
+    #[export]
+    #[init(val = Team::Player)]
+    team: Team,
+    #[export]
+    friendly_fire: bool,
+    #[export]
+    #[init(val = 0.1)]
+    crit_chance: f32,
+    #[export]
+    #[init(val = 1.5)]
+    crit_multiplier: f32,
+    #[export]
+    #[init(val = 1.5)]
+    backstab_multiplier: f32,
+    #[export]
+    #[init(val = 1.2)]
+    aerial_multiplier: f32,
+    #[export]
+    #[init(val = 1.)]
+    weight: f32,
+    #[export]
+    #[init(val = 2)]
+    max_juggles: u32,
+    #[export]
+    #[init(val = 0.4)]
+    wall_bounce: f32,
     #[var]
     invincible: bool,
+    #[export]
+    #[init(val = 150.)]
+    wall_slide_speed: f32,
+    #[export]
+    #[init(val = Vector2::new(450., 600.))]
+    wall_jump_power: Vector2,
+    #[export]
+    #[init(val = 0.2)]
+    wall_jump_lock: f64,
+    #[export]
+    #[init(val = 40.)]
+    ledge_reach: f32,
+    #[export]
+    #[init(val = -20.)]
+    ledge_hand_height: f32,
+    #[export]
+    #[init(val = 30.)]
+    ledge_clearance: f32,
+    #[export]
+    #[init(val = 52.)]
+    ledge_stand_offset: f32,
+    #[export]
+    #[init(val = 1.5)]
+    respawn_delay: f64,
+    #[export]
+    #[init(val = 0.5)]
+    fade_duration: f64,
+    #[export]
+    #[init(val = 2)]
+    effect_pool_size: i64,
+    // Seconds without landing a hit before the combo drops.
+    #[export]
+    #[init(val = 1.5)]
+    combo_timeout: f64,
+    // Every this many combo hits empower the player, 0 never does.
+    #[export]
+    #[init(val = 10)]
+    empower_combo: i64,
+
+    // Global, like the checkpoints and the saved position.
+    checkpoint: Vector2,
+    reset_enemies: bool,
+    dead: bool,
+    death_time: f64,
     #[var]
-    climbable: bool,
+    combo: i64,
+    #[var]
+    combo_time: f64,
 
     left: bool,
     right: bool,
@@ -38,8 +221,13 @@ pub struct Player {
     aura_attack: bool,
     fall_attack: bool,
     up: bool,
+    down: bool,
     climb: bool,
+    jump_held: bool,
+    jump_released: bool,
+    air_jump: bool,
 
+    #[var]
     flipped: bool,
     jumping: bool,
     falling: bool,
@@ -47,6 +235,7 @@ pub struct Player {
     hit: bool,
     suffering: bool,
     sliding: bool,
+    #[var]
     dashed: bool,
     dashing: bool,
     dash_finishing: bool,
@@ -59,19 +248,35 @@ pub struct Player {
     fall_attacking: bool,
     fall_attack_finishing: bool,
     climbing: bool,
+    climb_spots: Vec<ClimbSpot>,
+    climb_time: f64,
+    wall_sliding: bool,
+    wall_jump_time: f64,
+    wall_normal: f32,
+    ledge_grabbing: bool,
+    ledge_climbing: bool,
+    ledge_target: Vector2,
 
     dash_attack_delay: bool,
     aura_attack_delay: bool,
     fall_attack_delay: bool,
     climb_delay: bool,
 
-    strong_attack_shook: bool,
-    sword_aura_spawned: bool,
-
-    #[init(val=load("scene/sword_aura.tscn"))]
-    sword_aura: Gd<PackedScene>,
-    #[init(val=load("scene/ground_crack.tscn"))]
-    ground_crack: Gd<PackedScene>,
+    events: AnimationEvents,
+    flash: Flash,
+    // The hp the body was authored with, restored on respawn.
+    max_hp: f32,
+    last_hp: f32,
+    drop: DropThrough,
+    abilities: Abilities,
+    fall_attack_cooldown: f64,
+    statuses: StatusEffects,
+    knockback: Knockback,
+
+    #[init(val=EffectPool::new(load("scene/sword_aura.tscn")))]
+    sword_auras: EffectPool<SwordAura>,
+    #[init(val=EffectPool::new(load("scene/ground_crack.tscn")))]
+    ground_cracks: EffectPool<GroundCrack>,
 
     base: Base<CharacterBody2D>,
 }
@@ -79,7 +284,212 @@ pub struct Player {
 #[godot_api]
 impl Player {
     #[signal]
-    fn flip();
+    fn player_died();
+
+    #[signal]
+    fn player_respawned();
+
+    #[signal]
+    fn ability_changed(name: GString);
+
+    #[signal]
+    fn hit_landed(target: Gd<Node2D>, hit: Dictionary);
+
+    #[func]
+    pub fn effect_stats(&self) -> Dictionary {
+        dict! {
+            "sword_aura": self.sword_auras.stats(),
+            "ground_crack": self.ground_cracks.stats(),
+        }
+    }
+
+    // The parent is still adding children while we are ready.
+    #[func]
+    fn prewarm_effects(&mut self) {
+        let mut parent = self.base().get_parent().unwrap();
+        let size = self.effect_pool_size.max(0) as usize;
+
+        self.sword_auras.prewarm(&mut parent, size);
+        self.ground_cracks.prewarm(&mut parent, size);
+    }
+
+    #[func]
+    pub fn has_ability(&self, name: GString) -> bool {
+        ability(&name).is_some_and(|ability| self.abilities.unlocked(ability))
+    }
+
+    #[func]
+    pub fn ability_tier(&self, name: GString) -> i64 {
+        ability(&name).map_or(0, |ability| self.abilities.tier(ability))
+    }
+
+    #[func]
+    pub fn unlock_ability(&mut self, name: GString) -> bool {
+        let Some(ability) = ability(&name) else {
+            return false;
+        };
+
+        let changed = self.abilities.unlock(ability);
+
+        if changed {
+            self.base_mut()
+                .emit_signal("ability_changed", &[name.to_variant()]);
+        }
+
+        changed
+    }
+
+    #[func]
+    pub fn lock_ability(&mut self, name: GString) -> bool {
+        let Some(ability) = ability(&name) else {
+            return false;
+        };
+
+        let changed = self.abilities.lock(ability);
+
+        if changed {
+            self.base_mut()
+                .emit_signal("ability_changed", &[name.to_variant()]);
+        }
+
+        changed
+    }
+
+    #[func]
+    pub fn upgrade_ability(&mut self, name: GString) -> i64 {
+        let Some(ability) = ability(&name) else {
+            return 0;
+        };
+
+        if self.abilities.tier(ability) >= MAX_TIER {
+            return MAX_TIER;
+        }
+
+        let tier = self.abilities.upgrade(ability);
+
+        self.apply_abilities();
+        self.base_mut()
+            .emit_signal("ability_changed", &[name.to_variant()]);
+
+        tier
+    }
+
+    fn apply_abilities(&mut self) {
+        let tier = self.abilities.tier(Ability::FallAttack) as f64;
+        let cooldown =
+            self.fall_attack_cooldown * (1. - self.fall_attack_cooldown_per_tier * tier).max(0.1);
+        let radius = self.earthquake_radius();
+
+        self.base()
+            .get_node_as::<Timer>("FallAttackTimer")
+            .set_wait_time(cooldown);
+
+        let mut earthquake = self.base().get_node_as::<Hitbox>("Earthquake");
+        let scale = earthquake.get_scale();
+
+        earthquake.set_scale(Vector2::new(scale.x.signum() * radius, scale.y));
+    }
+
+    pub fn apply_effects(&mut self, effects: &[StatusEffect]) {
+        self.statuses.apply_all(effects);
+    }
+
+    pub fn knock_back(&mut self, impulse: Vector2, hitstun: f64) {
+        self.knockback
+            .launch(impulse, hitstun, self.weight, self.max_juggles);
+    }
+
+    fn clear_input(&mut self) {
+        self.left = false;
+        self.right = false;
+        self.jump = false;
+        self.slide = false;
+        self.dash = false;
+        self.basic_attack = false;
+        self.dash_attack = false;
+        self.aura_attack = false;
+        self.fall_attack = false;
+        self.up = false;
+        self.down = false;
+        self.climb = false;
+    }
+
+    fn move_speed(&self) -> f32 {
+        self.speed * self.statuses.speed_scale()
+    }
+
+    fn aura_reach(&self) -> f32 {
+        1. + self.aura_reach_per_tier * self.abilities.tier(Ability::AuraAttack) as f32
+    }
+
+    fn earthquake_radius(&self) -> f32 {
+        1. + self.earthquake_radius_per_tier * self.abilities.tier(Ability::FallAttack) as f32
+    }
+
+    fn camera(&self) -> Gd<SideCamera> {
+        self.base()
+            .get_parent()
+            .unwrap()
+            .get_node_as::<SideCamera>("SideCamera")
+    }
+
+    fn disarm(&mut self) {
+        for name in HITBOXES {
+            self.base().get_node_as::<Hitbox>(name).bind_mut().disarm();
+        }
+    }
+
+    fn begin_swing(&mut self) {
+        for name in HITBOXES {
+            let mut hitbox = self.base().get_node_as::<Hitbox>(name);
+            let mut hitbox = hitbox.bind_mut();
+
+            hitbox.disarm();
+            hitbox.begin_swing();
+        }
+    }
+
+    fn on_animation_event(&mut self, event: &str) {
+        match parse(event) {
+            ("hitbox", Some(path)) => {
+                self.disarm();
+                self.base()
+                    .get_node_as::<CollisionShape2D>(path)
+                    .set_disabled(false);
+            }
+            ("hitbox_off", _) => self.disarm(),
+            ("shake", Some(power)) => {
+                self.camera()
+                    .bind_mut()
+                    .shake(power.parse().unwrap_or_default());
+            }
+            ("spawn_aura", _) => self.spawn_aura(),
+            ("invincible", _) => self.invincible = true,
+            ("vulnerable", _) => self.invincible = false,
+            ("footstep", _) => {
+                let position = self.base().get_global_position();
+
+                play_sfx(self.base().get_tree(), Sfx::Footstep, position);
+            }
+            _ => godot_warn!("Unknown animation event {event}."),
+        }
+    }
+
+    fn spawn_aura(&mut self) {
+        let mut parent = self.base().get_parent().unwrap();
+        let mut sword_aura = self.sword_auras.acquire(&mut parent);
+        let direction = if self.flipped { -1 } else { 1 };
+
+        sword_aura.bind_mut().set_facing(direction);
+        sword_aura
+            .set_position(self.base().get_position() + Vector2::new(50. * direction as f32, 0.));
+        sword_aura.set_physics_process(true);
+        sword_aura.bind_mut().extend(self.aura_reach());
+        sword_aura.bind_mut().set_attacker(Attacker {
+            aerial: false,
+            ..self.attacker(self.team, self.friendly_fire)
+        });
+    }
 
     fn play_animation(&mut self, new: &str) {
         let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
@@ -89,6 +499,12 @@ impl Player {
         if old != new {
             self.on_animation_changed(&old, new);
 
+            if SWINGS.contains(&new) {
+                let position = self.base().get_global_position();
+
+                play_sfx(self.base().get_tree(), Sfx::Swing, position);
+            }
+
             animated.set_animation(new);
             animated.play();
         }
@@ -137,6 +553,18 @@ impl Player {
 
             animated.play();
         }
+
+        if old == "wall_slide" {
+            self.wall_sliding = false;
+        }
+
+        if old == "ledge_grab" && new != "ledge_climb" {
+            self.ledge_grabbing = false;
+        }
+
+        if old == "ledge_climb" {
+            self.ledge_climbing = false;
+        }
     }
 
     #[func]
@@ -196,139 +624,558 @@ impl Player {
             self.invincible = false;
             self.fall_attack_finishing = false;
         }
+
+        if animation == "ledge_climb" {
+            let target = self.ledge_target;
+
+            self.ledge_climbing = false;
+
+            self.base_mut().set_position(target);
+            self.play_animation("idle");
+        }
     }
 
     #[func]
     fn on_basic_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Enemy>() else {
-            return;
-        };
+        if self.strike("BasicAttack", body, &BASIC_ATTACK) {
+            self.gain_energy(self.energy_per_hit);
+        }
+    }
 
-        let hp: f32 = body.bind().get_hp();
+    #[func]
+    fn on_strong_body_entered(&mut self, body: Gd<Node2D>) {
+        self.strike("StrongAttack", body, &STRONG_ATTACK);
+    }
 
-        if !body.bind().get_invincible() && hp > 0. {
-            let resistance = body.bind().get_resistance();
+    #[func]
+    fn on_fall_body_entered(&mut self, body: Gd<Node2D>) {
+        self.strike("FallAttack", body, &FALL_ATTACK);
+    }
 
-            body.bind_mut()
-                .set_hp(hp - if resistance { 7.5 } else { 15. });
+    #[func]
+    fn on_earthquake_body_entered(&mut self, body: Gd<Node2D>) {
+        self.strike("Earthquake", body, &EARTHQUAKE);
+    }
 
-            if !resistance {
-                body.bind_mut().set_hit(true);
-                body.set_velocity(Vector2::new(0., -400.));
-            }
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            crit_chance: self.crit_chance,
+            crit_multiplier: self.crit_multiplier,
+            backstab_multiplier: self.backstab_multiplier,
+            aerial_multiplier: self.aerial_multiplier,
+            damage_scale: self.statuses.damage_scale(),
         }
     }
 
+    fn attacker(&self, team: Team, friendly_fire: bool) -> Attacker {
+        Attacker {
+            id: self.base().instance_id(),
+            team,
+            friendly_fire,
+            position: self.base().get_position(),
+            aerial: !self.base().is_on_floor(),
+            modifiers: self.modifiers(),
+        }
+    }
+
+    fn strike(&mut self, hitbox: &str, body: Gd<Node2D>, attack: &Attack) -> bool {
+        let hitbox = self.base().get_node_as::<Hitbox>(hitbox);
+        let attacker = self.attacker(hitbox.bind().get_team(), hitbox.bind().get_friendly_fire());
+
+        let Some(hit) = land(&attacker, attack, body.clone()) else {
+            return false;
+        };
+
+        self.combo += 1;
+        self.combo_time = self.combo_timeout;
+
+        if self.empower_combo > 0 && self.combo % self.empower_combo == 0 {
+            self.statuses.apply(EMPOWER);
+        }
+
+        self.base_mut().emit_signal(
+            "hit_landed",
+            &[body.to_variant(), hit.to_dictionary().to_variant()],
+        );
+
+        true
+    }
+
     #[func]
-    fn on_strong_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Enemy>() else {
+    fn on_dash_attack_timeout(&mut self) {
+        self.dash_attack_delay = false;
+    }
+
+    #[func]
+    fn on_aura_attack_timeout(&mut self) {
+        self.aura_attack_delay = false;
+    }
+
+    #[func]
+    fn on_fall_attack_timeout(&mut self) {
+        self.fall_attack_delay = false;
+    }
+
+    #[func]
+    fn on_climb_timeout(&mut self) {
+        self.climb_delay = false;
+    }
+
+    fn face(&mut self, flipped: bool) {
+        self.flipped = flipped;
+        self.base()
+            .get_node_as::<Facing>("Facing")
+            .bind_mut()
+            .set_facing(if flipped { -1 } else { 1 });
+    }
+
+    fn update_wall(&mut self, velocity: &mut Vector2, jump_pressed: bool) {
+        if self.ledge_climbing {
+            *velocity = Vector2::ZERO;
+            return;
+        }
+
+        let (left, right) = (self.left, self.right);
+        let toward = |normal: f32| (left && normal > 0.) || (right && normal < 0.);
+        let away = |normal: f32| (left && normal < 0.) || (right && normal > 0.);
+
+        if self.ledge_grabbing {
+            *velocity = Vector2::ZERO;
+
+            if self.up || toward(self.wall_normal) {
+                self.ledge_grabbing = false;
+                self.ledge_climbing = true;
+                self.play_animation("ledge_climb");
+            } else if self.down || away(self.wall_normal) {
+                self.ledge_grabbing = false;
+                self.falling = true;
+            }
+
+            return;
+        }
+
+        let busy = self.suffering
+            || self.sliding
+            || self.dashing
+            || self.basic_attacking
+            || self.dash_attacking
+            || self.dash_attack_finishing
+            || self.aura_attacking
+            || self.fall_attacking
+            || self.fall_attack_finishing
+            || self.climbing;
+
+        let on_wall = self.base().is_on_wall_only();
+        let normal = self.base().get_wall_normal().x;
+
+        if !on_wall || busy || velocity.y < 0. || !toward(normal) {
+            self.wall_sliding = false;
+        } else if let Some(target) = self.find_ledge(-normal.signum()) {
+            *velocity = Vector2::ZERO;
+
+            self.wall_normal = normal;
+            self.wall_sliding = false;
+            self.ledge_grabbing = true;
+            self.ledge_target = target;
+            self.jumping = false;
+            self.falling = false;
+            self.dashed = false;
+            self.air_jump = self.double_jump;
+
+            self.play_animation("ledge_grab");
             return;
+        } else {
+            velocity.y = velocity.y.min(self.wall_slide_speed);
+
+            self.wall_normal = normal;
+            self.wall_sliding = true;
+            self.dashed = false;
+            self.air_jump = self.double_jump;
+
+            self.play_animation("wall_slide");
+        }
+
+        if self.wall_sliding && jump_pressed {
+            let power = self.wall_jump_power;
+            let direction = self.wall_normal.signum();
+
+            velocity.x = power.x * direction;
+            velocity.y = -power.y;
+
+            self.wall_sliding = false;
+            self.wall_jump_time = self.wall_jump_lock;
+            self.jumping = true;
+            self.jump_released = false;
+            self.falling = false;
+
+            self.face(direction < 0.);
+            self.play_animation("jump");
+        }
+    }
+
+    fn accelerate(&self, current: f32, target: f32, delta: f64) -> f32 {
+        let turning = current * target < 0.;
+        let rate = match (self.base().is_on_floor(), turning) {
+            (true, false) => self.ground_acceleration,
+            (true, true) => self.ground_acceleration.max(self.ground_deceleration),
+            (false, false) => self.air_acceleration,
+            (false, true) => self.air_acceleration.max(self.air_deceleration),
         };
 
-        let hp: f32 = body.bind().get_hp();
+        let scale = self.sample(&self.acceleration_curve, current);
+
+        move_toward(current as f64, target as f64, (rate * scale) as f64 * delta) as f32
+    }
+
+    fn decelerate(&self, current: f32, delta: f64) -> f32 {
+        let rate = if self.base().is_on_floor() {
+            self.ground_deceleration
+        } else {
+            self.air_deceleration
+        };
 
-        if !body.bind().get_invincible() && hp > 0. {
-            let resistance = body.bind().get_resistance();
+        let scale = self.sample(&self.deceleration_curve, current);
 
-            body.bind_mut()
-                .set_hp(hp - if resistance { 25. } else { 35. });
+        move_toward(current as f64, 0., (rate * scale) as f64 * delta) as f32
+    }
 
-            if !resistance {
-                body.bind_mut().set_hit(true);
-                body.set_velocity(Vector2::new(0., 400.));
-            }
+    fn sample(&self, curve: &Option<Gd<Curve>>, current: f32) -> f32 {
+        let ratio = if self.speed > 0. {
+            (current.abs() / self.speed).min(1.)
+        } else {
+            1.
+        };
+
+        curve.as_ref().map_or(1., |curve| curve.sample(ratio))
+    }
+
+    fn find_ledge(&self, direction: f32) -> Option<Vector2> {
+        let mut space = self.base().get_world_2d()?.get_direct_space_state()?;
+        let exclude = Array::from(&[self.base().get_rid()]);
+
+        let position = self.base().get_global_position();
+        let reach = Vector2::new(direction * self.ledge_reach, 0.);
+        let hand = position + Vector2::new(0., self.ledge_hand_height);
+        let above = hand - Vector2::new(0., self.ledge_clearance);
+
+        cast_ray(&mut space, hand, hand + reach, &exclude)?;
+
+        if cast_ray(&mut space, above, above + reach, &exclude).is_some() {
+            return None;
         }
+
+        let top = cast_ray(
+            &mut space,
+            above + reach,
+            hand + reach + Vector2::new(0., 4.),
+            &exclude,
+        )?;
+
+        Some(
+            self.base().get_position() + Vector2::new(top.x, top.y - self.ledge_stand_offset)
+                - position,
+        )
     }
 
-    #[func]
-    fn on_fall_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Enemy>() else {
+    pub fn enter_climbable(&mut self, spot: ClimbSpot) {
+        self.climb_spots.push(spot);
+    }
+
+    pub fn exit_climbable(&mut self, area: InstanceId, body_shape: i32, shape: i32) {
+        if let Some(index) = self.climb_spots.iter().position(|spot| {
+            spot.area == area && spot.body_shape == body_shape && spot.shape == shape
+        }) {
+            self.climb_spots.swap_remove(index);
+        }
+    }
+
+    fn climbable(&self) -> bool {
+        !self.climb_spots.is_empty()
+    }
+
+    fn climb_spot(&self) -> Option<ClimbSpot> {
+        let x = self.base().get_global_position().x;
+
+        self.climb_spots
+            .iter()
+            .min_by(|a, b| (a.centre - x).abs().total_cmp(&(b.centre - x).abs()))
+            .cloned()
+    }
+
+    fn update_climb(&mut self, velocity: &mut Vector2, delta: f64) {
+        if !self.climbing {
+            self.climb_time = 0.;
+            return;
+        }
+
+        let Some(spot) = self.climb_spot() else {
             return;
         };
 
-        let hp: f32 = body.bind().get_hp();
+        let mut position = self.base().get_global_position();
 
-        if !body.bind().get_invincible() && hp > 0. {
-            let resistance = body.bind().get_resistance();
+        self.climb_time += delta;
 
-            body.bind_mut()
-                .set_hp(hp - if resistance { 25. } else { 35. });
+        if self.up && position.y + self.ledge_hand_height <= spot.top {
+            position.y = spot.top - self.ledge_stand_offset;
+            velocity.y = 0.;
 
-            if !resistance {
-                body.bind_mut().set_hit(true);
-                body.set_velocity(Vector2::new(0., 400.));
-            }
+            self.climbing = false;
+            self.base_mut().set_global_position(position);
+            self.play_animation("idle");
+
+            return;
         }
-    }
 
-    #[func]
-    fn on_earthquake_body_entered(&mut self, body: Gd<Node2D>) {
-        let Ok(mut body) = body.try_cast::<Enemy>() else {
+        if spot.kind == ClimbKind::Vine {
             return;
+        }
+
+        let sway = if spot.kind == ClimbKind::Rope {
+            spot.sway_amplitude * (self.climb_time * spot.sway_speed as f64).sin() as f32
+        } else {
+            0.
         };
 
-        let hp: f32 = body.bind().get_hp();
+        position.x = spot.centre + sway;
+        velocity.x = 0.;
 
-        if !body.bind().get_invincible() && hp > 0. {
-            let resistance = body.bind().get_resistance();
+        self.base_mut().set_global_position(position);
+    }
 
-            body.bind_mut()
-                .set_hp(hp - if resistance { 30. } else { 50. });
+    pub fn checkpoint(&self) -> Vector2 {
+        self.checkpoint
+    }
 
-            if !resistance {
-                let velocity = if self.base().get_position().x - body.get_position().x < 0. {
-                    1000.
-                } else {
-                    -1000.
-                };
+    pub fn set_checkpoint(&mut self, position: Vector2, reset_enemies: bool) {
+        self.checkpoint = position;
+        self.reset_enemies = reset_enemies;
+    }
+
+    fn die(&mut self, delta: f64) {
+        if !self.dead {
+            self.dead = true;
+            self.death_time = 0.;
+
+            self.base_mut().emit_signal("player_died", &[]);
 
-                body.bind_mut().set_hit(true);
-                body.set_velocity(Vector2::new(velocity, -1500.));
+            if let Some(mut state) = game_state(self.base().get_tree()) {
+                state.bind_mut().player_died();
             }
         }
+
+        self.death_time += delta;
+
+        if self.death_time < self.respawn_delay {
+            return;
+        }
+
+        let fade = self
+            .base()
+            .get_tree()
+            .and_then(|tree| tree.get_first_node_in_group("fade"))
+            .and_then(|fade| fade.try_cast::<ScreenFade>().ok());
+
+        let Some(mut fade) = fade else {
+            self.respawn();
+            return;
+        };
+
+        if fade.bind().is_covered() {
+            self.respawn();
+            fade.bind_mut().fade_in(self.fade_duration);
+        } else {
+            fade.bind_mut().fade_out(self.fade_duration);
+        }
     }
 
     #[func]
-    fn on_dash_attack_timeout(&mut self) {
+    pub fn respawn(&mut self) {
+        let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
+        let checkpoint = self.checkpoint;
+
+        self.hp = self.max_hp;
+        self.energy = self.max_energy;
+        self.statuses.clear();
+        self.knockback.clear();
+        self.dead = false;
+        self.combo = 0;
+        self.combo_time = 0.;
+        self.hit = false;
+        self.suffering = false;
+        self.invincible = false;
+        self.climbing = false;
+        self.jumping = false;
+        self.falling = false;
+        self.sliding = false;
+        self.dashed = false;
+        self.dashing = false;
+        self.dash_finishing = false;
+        self.basic_attacking = false;
+        self.dash_attacking = false;
+        self.dash_attack_finishing = false;
+        self.aura_attacking = false;
+        self.fall_attacking = false;
+        self.fall_attack_finishing = false;
+
+        for name in [
+            "DashAttackTimer",
+            "AuraAttackTimer",
+            "FallAttackTimer",
+            "ClimbTimer",
+        ] {
+            self.resume_cooldown(name, None);
+        }
+
         self.dash_attack_delay = false;
+        self.aura_attack_delay = false;
+        self.fall_attack_delay = false;
+        self.climb_delay = false;
+
+        self.base()
+            .get_node_as::<CollisionShape2D>("BodyCollision")
+            .set_one_way_collision(true);
+
+        self.base_mut().set_global_position(checkpoint);
+        self.base_mut().set_velocity(Vector2::ZERO);
+
+        animated.set_frame(0);
+        self.play_animation("idle");
+
+        if self.reset_enemies {
+            if let Some(tree) = self.base().get_tree() {
+                for enemy in tree.get_nodes_in_group("enemy").iter_shared() {
+                    if let Ok(mut enemy) = enemy.try_cast::<Enemy>() {
+                        enemy.bind_mut().reset();
+                    }
+                }
+            }
+        }
+
+        self.base_mut().emit_signal("player_respawned", &[]);
     }
 
-    #[func]
-    fn on_aura_attack_timeout(&mut self) {
-        self.aura_attack_delay = false;
+    pub fn save(&self) -> Dictionary {
+        let cooldown = |name: &str| self.base().get_node_as::<Timer>(name).get_time_left();
+
+        // A player saved while dying is loaded as if already respawned.
+        let (hp, position) = if self.dead || self.hp <= 0. {
+            (self.max_hp, self.checkpoint)
+        } else {
+            (self.hp, self.base().get_global_position())
+        };
+
+        dict! {
+            "hp": hp,
+            "energy": self.energy,
+            "abilities": self.abilities.save(),
+            "position": vector(position),
+            "checkpoint": vector(self.checkpoint),
+            "reset_enemies": self.reset_enemies,
+            "dashed": self.dashed,
+            "cooldowns": dict! {
+                "dash_attack": cooldown("DashAttackTimer"),
+                "aura_attack": cooldown("AuraAttackTimer"),
+                "fall_attack": cooldown("FallAttackTimer"),
+            },
+        }
     }
 
-    #[func]
-    fn on_fall_attack_timeout(&mut self) {
-        self.fall_attack_delay = false;
+    pub fn restore(&mut self, data: &Dictionary) {
+        self.hp = read(data, "hp")
+            .filter(|&hp: &f32| hp > 0.)
+            .unwrap_or(self.max_hp);
+        self.energy = read(data, "energy").unwrap_or(self.max_energy);
+
+        if let Some(abilities) = read::<Dictionary>(data, "abilities") {
+            self.abilities.restore(&abilities);
+            self.apply_abilities();
+        }
+        self.dashed = read(data, "dashed").unwrap_or(false);
+        self.reset_enemies = read(data, "reset_enemies").unwrap_or(false);
+
+        if let Some(checkpoint) = read_vector(data, "checkpoint") {
+            self.checkpoint = checkpoint;
+        }
+
+        if let Some(position) = read_vector(data, "position") {
+            self.base_mut().set_global_position(position);
+            self.base_mut().set_velocity(Vector2::ZERO);
+        }
+
+        let cooldowns = read::<Dictionary>(data, "cooldowns").unwrap_or_default();
+
+        self.dash_attack_delay =
+            self.resume_cooldown("DashAttackTimer", read(&cooldowns, "dash_attack"));
+        self.aura_attack_delay =
+            self.resume_cooldown("AuraAttackTimer", read(&cooldowns, "aura_attack"));
+        self.fall_attack_delay =
+            self.resume_cooldown("FallAttackTimer", read(&cooldowns, "fall_attack"));
     }
 
-    #[func]
-    fn on_climb_timeout(&mut self) {
-        self.climb_delay = false;
+    pub fn skill_cost(&self, timer: &str) -> f32 {
+        match timer {
+            "DashAttackTimer" => self.dash_attack_cost,
+            "AuraAttackTimer" => self.aura_attack_cost,
+            "FallAttackTimer" => self.fall_attack_cost,
+            _ => 0.,
+        }
+    }
+
+    fn gain_energy(&mut self, amount: f32) {
+        self.energy = (self.energy + amount).clamp(0., self.max_energy);
+    }
+
+    fn resume_cooldown(&mut self, name: &str, left: Option<f64>) -> bool {
+        let mut timer = self.base().get_node_as::<Timer>(name);
+        let wait_time = timer.get_wait_time();
+
+        match left {
+            Some(left) if left > 0. => {
+                // Starting with a custom time also overwrites the wait time.
+                timer.start_ex().time_sec(left).done();
+                timer.set_wait_time(wait_time);
+                true
+            }
+            _ => {
+                timer.stop();
+                false
+            }
+        }
     }
 }
 
 #[godot_api]
 impl ICharacterBody2D for Player {
-    fn draw(&mut self) {
-        if self.hp > 0. {
-            let hp = self.hp;
-            let invincible = self.invincible;
-
-            self.base_mut().draw_rect(
-                Rect2::new(Vector2::new(-52., 73.), Vector2::new(104., 9.)),
-                Color::BLACK,
-            );
+    fn ready(&mut self) {
+        let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
+        let mut body = self.to_gd().upcast::<CharacterBody2D>();
+
+        self.flash.attach(&mut animated);
+        self.drop.attach(&mut body);
+        self.max_hp = self.hp;
+        self.last_hp = self.hp;
+        self.checkpoint = self.base().get_global_position();
+        self.fall_attack_cooldown = self
+            .base()
+            .get_node_as::<Timer>("FallAttackTimer")
+            .get_wait_time();
 
-            self.base_mut().draw_rect(
-                Rect2::new(Vector2::new(-50., 75.), Vector2::new(hp, 5.)),
-                if invincible {
-                    Color::WHITE
-                } else {
-                    Color::GREEN
-                },
-            );
+        for name in self.locked_abilities.as_slice() {
+            if let Some(ability) = ability(name) {
+                self.abilities.lock(ability);
+            }
+        }
+
+        self.apply_abilities();
+        self.base_mut().add_to_group("player");
+        self.base_mut().call_deferred("prewarm_effects", &[]);
+
+        for name in HITBOXES {
+            self.base()
+                .get_node_as::<Hitbox>(name)
+                .bind_mut()
+                .inherit(self.team, self.friendly_fire);
         }
     }
 
@@ -339,17 +1186,23 @@ impl ICharacterBody2D for Player {
         self.right = input.is_key_pressed(Key::RIGHT);
         self.jump = input.is_key_pressed(Key::SPACE);
 
-        self.slide = input.is_action_just_pressed("slide");
-        self.dash = input.is_action_just_pressed("dash");
+        let unlocked = |ability| self.abilities.unlocked(ability);
+
+        self.slide = input.is_action_just_pressed("slide") && unlocked(Ability::Slide);
+        self.dash = input.is_action_just_pressed("dash") && unlocked(Ability::Dash);
 
         self.basic_attack = input.is_action_just_pressed("basic_attack");
 
-        self.dash_attack = input.is_action_just_pressed("dash_attack");
-        self.aura_attack = input.is_action_just_pressed("aura_attack");
-        self.fall_attack = input.is_action_just_pressed("fall_attack");
+        self.dash_attack =
+            input.is_action_just_pressed("dash_attack") && unlocked(Ability::DashAttack);
+        self.aura_attack =
+            input.is_action_just_pressed("aura_attack") && unlocked(Ability::AuraAttack);
+        self.fall_attack =
+            input.is_action_just_pressed("fall_attack") && unlocked(Ability::FallAttack);
 
         self.up = input.is_key_pressed(Key::UP);
-        self.climb = input.is_action_just_pressed("climb");
+        self.down = input.is_key_pressed(Key::DOWN);
+        self.climb = input.is_action_just_pressed("climb") && unlocked(Ability::Climb);
 
         if self.slide || self.dash || self.basic_attack || self.aura_attack || self.climb {
             self.dash_finishing = false;
@@ -357,61 +1210,89 @@ impl ICharacterBody2D for Player {
     }
 
     fn physics_process(&mut self, delta: f64) {
-        self.base_mut().queue_redraw();
+        if self.hp < self.last_hp {
+            self.flash.trigger();
+        }
 
-        let gravity = ProjectSettings::singleton()
-            .get_setting("physics/2d/default_gravity")
-            .to::<f32>()
-            / 35.;
+        self.last_hp = self.hp;
+        self.flash.update(delta);
+
+        let mut body = self.to_gd().upcast::<CharacterBody2D>();
+
+        self.drop.update(&mut body, delta);
+
+        let status_damage = self.statuses.update(delta);
+
+        if status_damage > 0. && self.hp > 0. {
+            self.hp -= status_damage;
+            show_damage(
+                self.base().get_tree(),
+                self.base().get_global_position(),
+                status_damage,
+                DamageKind::Status,
+            );
+        }
+
+        if self.statuses.stunned() {
+            self.clear_input();
+        }
+
+        let gravity = physics::gravity();
 
         let mut velocity = self.base().get_velocity();
         let mut animated = self.base().get_node_as::<AnimatedSprite2D>("Animation");
 
-        let mut basic_collision = self
-            .base()
-            .get_node_as::<Hitbox>("BasicAttack")
-            .get_node_as::<CollisionShape2D>("Collision");
-        let mut strong_collision = self
-            .base()
-            .get_node_as::<Hitbox>("StrongAttack")
-            .get_node_as::<CollisionShape2D>("Collision");
-        let mut fall_collision = self
-            .base()
-            .get_node_as::<Hitbox>("FallAttack")
-            .get_node_as::<CollisionShape2D>("Collision");
-        let mut earthquake_collision = self
-            .base()
-            .get_node_as::<Hitbox>("Earthquake")
-            .get_node_as::<CollisionShape2D>("Collision");
+        animated.set_self_modulate(self.statuses.tint());
 
         velocity.y = if self.fall_attacking {
-            (velocity.y + 300. + gravity * 1.5 + delta as f32).min(1200.)
-        } else if !self.base().is_on_floor() && !self.climbing {
-            (velocity.y + gravity + delta as f32).min(750.)
+            fall(
+                velocity.y,
+                self.fall_attack_acceleration + gravity * 1.5,
+                1200.,
+                delta,
+            )
+        } else if !self.base().is_on_floor()
+            && !self.climbing
+            && !self.ledge_grabbing
+            && !self.ledge_climbing
+        {
+            fall(velocity.y, gravity, 750., delta)
         } else {
             0.
         };
 
+        let knocked = self
+            .knockback
+            .update(&body, &mut velocity, self.wall_bounce, delta);
+
         if self.hp <= 0. {
-            velocity.x = velocity.x.lerp(0., 0.1);
+            velocity.x = damp(velocity.x, 0., 0.1, delta);
 
-            basic_collision.set_disabled(true);
-            strong_collision.set_disabled(true);
-            fall_collision.set_disabled(true);
-            earthquake_collision.set_disabled(true);
+            self.disarm();
 
             self.base_mut().move_and_slide();
             self.base_mut().set_velocity(velocity);
             self.play_animation("death");
+            self.die(delta);
 
             return;
         } else {
-            self.hp = (self.hp + 0.1).min(100.)
+            self.hp = regen(self.hp, self.hp_regen, self.max_hp, delta);
+            self.energy = regen(self.energy, self.energy_regen, self.max_energy, delta);
+        }
+
+        self.combo_time = (self.combo_time - delta).max(0.);
+
+        if self.combo_time <= 0. {
+            self.combo = 0;
         }
 
         if self.hit {
             self.hit = false;
             self.suffering = true;
+            self.wall_sliding = false;
+            self.ledge_grabbing = false;
+            self.ledge_climbing = false;
 
             animated.set_frame(0);
             self.play_animation("hit");
@@ -420,76 +1301,16 @@ impl ICharacterBody2D for Player {
         let animation = animated.get_animation().to_string();
         let frame = animated.get_frame();
 
-        let mut camera = self
-            .base()
-            .get_parent()
-            .unwrap()
-            .get_node_as::<SideCamera>("SideCamera");
-
-        match frame {
-            0..=5 if animation == "slide" => {
-                basic_collision.set_disabled(true);
-                strong_collision.set_disabled(true);
-                fall_collision.set_disabled(true);
-                earthquake_collision.set_disabled(true);
-
-                self.invincible = true;
-            }
-            6 if animation == "slide" => {
-                self.invincible = false;
-            }
-            5..=6 | 9..=10 if animation == "basic_attack" => {
-                basic_collision.set_disabled(false);
-                strong_collision.set_disabled(true);
-                fall_collision.set_disabled(true);
-                earthquake_collision.set_disabled(true);
-            }
-            3..=4 if animation == "aura_attack" || animation == "dash_attack_finished" => {
-                basic_collision.set_disabled(true);
-                strong_collision.set_disabled(false);
-                fall_collision.set_disabled(true);
-                earthquake_collision.set_disabled(true);
-
-                if !self.strong_attack_shook {
-                    self.strong_attack_shook = true;
-                    camera.bind_mut().shake(30);
-                }
-
-                if !self.sword_aura_spawned && animation == "aura_attack" {
-                    let mut sword_aura = self.sword_aura.instantiate().unwrap().cast::<SwordAura>();
+        let (started, events) = self.events.update(MARKERS, &animation, frame);
 
-                    self.sword_aura_spawned = true;
-
-                    self.base().get_parent().unwrap().add_child(&sword_aura);
-
-                    sword_aura.bind_mut().set_flipped(self.flipped);
-                    sword_aura.set_scale(Vector2::new(if self.flipped { -1. } else { 1. }, 1.));
-
-                    sword_aura.set_position(self.base().get_position() + Vector2::new(50., 0.));
-                    sword_aura.set_physics_process(true);
-                }
-            }
-            _ if animation == "fall_attack" => {
-                basic_collision.set_disabled(true);
-                strong_collision.set_disabled(true);
-                fall_collision.set_disabled(false);
-                earthquake_collision.set_disabled(true);
-            }
-            1 if animation == "fall_attack_finished" => {
-                basic_collision.set_disabled(true);
-                strong_collision.set_disabled(true);
-                fall_collision.set_disabled(true);
-                earthquake_collision.set_disabled(false);
-            }
-            _ => {
-                self.strong_attack_shook = false;
-                self.sword_aura_spawned = false;
+        // Covers replays of the same animation as well as switches, so a
+        // restarted attack can hit the same targets again.
+        if started {
+            self.begin_swing();
+        }
 
-                basic_collision.set_disabled(true);
-                strong_collision.set_disabled(true);
-                fall_collision.set_disabled(true);
-                earthquake_collision.set_disabled(true);
-            }
+        for event in events {
+            self.on_animation_event(event);
         }
 
         let mut dash_attack_timer = self.base().get_node_as::<Timer>("DashAttackTimer");
@@ -497,8 +1318,20 @@ impl ICharacterBody2D for Player {
         let mut fall_attack_timer = self.base().get_node_as::<Timer>("FallAttackTimer");
         let mut climb_timer = self.base().get_node_as::<Timer>("ClimbTimer");
 
-        if (self.left || self.right || self.fall_attack || (self.up && self.climbing))
+        let jump_pressed = self.jump && !self.jump_held;
+        self.jump_held = self.jump;
+        self.wall_jump_time = (self.wall_jump_time - delta).max(0.);
+
+        let free_climb = self
+            .climb_spot()
+            .is_some_and(|spot| spot.kind == ClimbKind::Vine);
+
+        if (self.left
+            || self.right
+            || self.fall_attack
+            || ((self.up || self.down) && self.climbing))
             && !self.suffering
+            && !self.knockback.stunned()
             && !self.sliding
             && !self.dashing
             && !self.basic_attacking
@@ -507,16 +1340,22 @@ impl ICharacterBody2D for Player {
             && !self.aura_attacking
             && !self.fall_attacking
             && !self.fall_attack_finishing
+            && !self.ledge_grabbing
+            && !self.ledge_climbing
         {
-            if self.left && !self.up {
+            if self.left && !self.up && self.wall_jump_time <= 0. {
                 self.dash_finishing = false;
 
-                velocity.x = -self.speed * if self.climbing { 0.5 } else { 1. };
-                animated.set_flip_h(true);
-                if !self.flipped {
-                    self.base_mut().emit_signal("flip", &[]);
-                    self.flipped = true;
-                }
+                velocity.x = if self.climbing {
+                    if free_climb {
+                        -self.speed * 0.5
+                    } else {
+                        0.
+                    }
+                } else {
+                    self.accelerate(velocity.x, -self.move_speed(), delta)
+                };
+                self.face(true);
 
                 if !self.jumping && !self.falling && !self.climbing {
                     self.play_animation("run");
@@ -525,15 +1364,19 @@ impl ICharacterBody2D for Player {
                 }
             }
 
-            if self.right && !self.up {
+            if self.right && !self.up && self.wall_jump_time <= 0. {
                 self.dash_finishing = false;
 
-                velocity.x = self.speed * if self.climbing { 0.5 } else { 1. };
-                animated.set_flip_h(false);
-                if self.flipped {
-                    self.base_mut().emit_signal("flip", &[]);
-                    self.flipped = false;
-                }
+                velocity.x = if self.climbing {
+                    if free_climb {
+                        self.speed * 0.5
+                    } else {
+                        0.
+                    }
+                } else {
+                    self.accelerate(velocity.x, self.move_speed(), delta)
+                };
+                self.face(false);
 
                 if !self.jumping && !self.falling && !self.climbing {
                     self.play_animation("run");
@@ -550,6 +1393,14 @@ impl ICharacterBody2D for Player {
                 animated.play();
             }
 
+            if self.down && !self.up && self.climbing && velocity.x == 0. {
+                self.dash_finishing = false;
+
+                velocity.y = self.speed * 0.5;
+
+                animated.play();
+            }
+
             if self.slide && !self.falling && !self.jumping && !self.climbing {
                 self.sliding = true;
 
@@ -582,10 +1433,13 @@ impl ICharacterBody2D for Player {
             if self.fall_attack
                 && !self.fall_attacking
                 && !self.fall_attack_delay
+                && self.energy >= self.fall_attack_cost
                 && !self.base().is_on_floor()
             {
                 fall_attack_timer.start();
 
+                self.energy -= self.fall_attack_cost;
+
                 self.invincible = true;
                 self.fall_attacking = true;
                 self.fall_attack_delay = true;
@@ -606,6 +1460,8 @@ impl ICharacterBody2D for Player {
             && !self.fall_attacking
             && !self.fall_attack_finishing
             && !self.climbing
+            && !self.ledge_grabbing
+            && !self.ledge_climbing
         {
             if self.basic_attack {
                 self.basic_attacking = true;
@@ -613,9 +1469,11 @@ impl ICharacterBody2D for Player {
                 self.play_animation("basic_attack");
             }
 
-            if self.dash_attack && !self.dash_attack_delay {
+            if self.dash_attack && !self.dash_attack_delay && self.energy >= self.dash_attack_cost {
                 dash_attack_timer.start();
 
+                self.energy -= self.dash_attack_cost;
+
                 self.dash_attacking = true;
                 self.dash_attack_delay = true;
                 self.invincible = true;
@@ -625,9 +1483,11 @@ impl ICharacterBody2D for Player {
                     .set_one_way_collision(false);
             }
 
-            if self.aura_attack && !self.aura_attack_delay {
+            if self.aura_attack && !self.aura_attack_delay && self.energy >= self.aura_attack_cost {
                 aura_attack_timer.start();
 
+                self.energy -= self.aura_attack_cost;
+
                 self.aura_attacking = true;
                 self.aura_attack_delay = true;
                 self.play_animation("aura_attack");
@@ -642,53 +1502,61 @@ impl ICharacterBody2D for Player {
             }
         }
 
+        self.update_wall(&mut velocity, jump_pressed);
+
         if velocity.y > 0. && !self.dashing && !self.fall_attacking {
             self.jumping = false;
             self.falling = true;
             self.dash_finishing = false;
 
-            if !self.suffering {
+            if !self.suffering && !self.wall_sliding {
                 self.play_animation("fall");
             }
         }
 
         if self.base().is_on_floor() {
+            if self.falling {
+                let position = self.base().get_global_position();
+
+                play_sfx(self.base().get_tree(), Sfx::Land, position);
+            }
+
             self.jumping = false;
             self.falling = false;
             self.dashed = false;
+            self.air_jump = self.double_jump;
 
             if self.fall_attacking {
-                let mut ground_crack = self
-                    .ground_crack
-                    .instantiate()
-                    .unwrap()
-                    .cast::<GroundCrack>();
+                let mut parent = self.base().get_parent().unwrap();
+                let mut ground_crack = self.ground_cracks.acquire(&mut parent);
 
                 self.fall_attacking = false;
                 self.fall_attack = false;
                 self.fall_attacking = false;
                 self.fall_attack_finishing = true;
 
-                camera.bind_mut().shake(75);
+                self.camera().bind_mut().shake(75);
                 self.play_animation("fall_attack_finished");
 
-                self.base().get_parent().unwrap().add_child(&ground_crack);
-
                 ground_crack.set_position(self.base().get_position() + Vector2::new(0., 55.));
+                ground_crack.set_scale(Vector2::new(self.earthquake_radius(), 1.));
                 ground_crack.set_physics_process(true);
+                ground_crack
+                    .bind_mut()
+                    .set_attacker(self.attacker(self.team, self.friendly_fire));
             }
         }
 
-        if !self.climbable {
+        if !self.climbable() {
             self.climbing = false;
         }
 
-        if self.climbing && !self.left && !self.right && !self.up {
+        if self.climbing && !self.left && !self.right && !self.up && !self.down {
             animated.pause();
         }
 
         if self.climb
-            && self.climbable
+            && self.climbable()
             && !self.climbing
             && !self.climb_delay
             && !self.suffering
@@ -708,6 +1576,8 @@ impl ICharacterBody2D for Player {
             self.play_animation("climb");
         }
 
+        self.update_climb(&mut velocity, delta);
+
         if ((!self.left && !self.right)
             || self.basic_attacking
             || self.dash_attack_finishing
@@ -718,8 +1588,9 @@ impl ICharacterBody2D for Player {
             && !self.sliding
             && !self.dashing
             && !self.dash_attacking
+            && self.wall_jump_time <= 0.
         {
-            velocity.x = move_toward(velocity.x.into(), 0., self.speed.into()) as f32;
+            velocity.x = self.decelerate(velocity.x, delta);
 
             if !self.jumping
                 && !self.falling
@@ -730,13 +1601,16 @@ impl ICharacterBody2D for Player {
                 && !self.fall_attacking
                 && !self.fall_attack_finishing
                 && !self.climbing
+                && !self.ledge_grabbing
+                && !self.ledge_climbing
             {
                 self.play_animation("idle");
             }
         }
 
-        if self.jump
-            && (self.base().is_on_floor() || self.climbing)
+        let grounded =
+            self.base().is_on_floor() || self.climbing || (self.ledge_grabbing && jump_pressed);
+        let jumpable = !self.ledge_climbing
             && !self.suffering
             && !self.sliding
             && !self.dashing
@@ -745,21 +1619,72 @@ impl ICharacterBody2D for Player {
             && !self.dash_attack_finishing
             && !self.aura_attacking
             && !self.fall_attacking
-            && !self.fall_attack_finishing
-        {
+            && !self.fall_attack_finishing;
+
+        if jump_pressed && self.down && jumpable && !self.climbing && self.drop.on_platform(&body) {
+            self.drop.start(&mut body);
+            self.falling = true;
+
+            self.play_animation("fall");
+        } else if self.jump && grounded && jumpable && !self.drop.dropping() {
             self.jumping = true;
+            self.jump_released = false;
+            self.air_jump = self.double_jump;
             self.dash_finishing = false;
             self.climbing = false;
+            self.ledge_grabbing = false;
 
             self.play_animation("jump");
             velocity.y = -self.jump_power;
+        } else if jump_pressed
+            && jumpable
+            && self.air_jump
+            && !self.wall_sliding
+            && !self.ledge_grabbing
+        {
+            self.jumping = true;
+            self.jump_released = false;
+            self.air_jump = false;
+            self.dash_finishing = false;
+
+            animated.set_frame(0);
+            self.play_animation("jump");
+            velocity.y = -self.double_jump_power;
+        }
+
+        if !self.jump && self.jumping && !self.jump_released && velocity.y < 0. {
+            self.jump_released = true;
+
+            velocity.y *= self.jump_cut;
         }
 
-        if self.suffering {
-            velocity.x = velocity.x.lerp(0., 0.1);
+        if self.suffering && !knocked {
+            velocity.x = damp(velocity.x, 0., 0.1, delta);
         }
 
         self.base_mut().move_and_slide();
         self.base_mut().set_velocity(velocity);
     }
 }
+
+fn cast_ray(
+    space: &mut Gd<PhysicsDirectSpaceState2D>,
+    from: Vector2,
+    to: Vector2,
+    exclude: &Array<Rid>,
+) -> Option<Vector2> {
+    let mut query = PhysicsRayQueryParameters2D::create(from, to)?;
+    query.set_exclude(exclude);
+
+    read(&space.intersect_ray(&query), "position")
+}
+
+fn ability(name: &GString) -> Option<Ability> {
+    let ability = Ability::from_name(&name.to_string());
+
+    if ability.is_none() {
+        godot_warn!("Unknown ability {name}.");
+    }
+
+    ability
+}
@@ -0,0 +1,118 @@
+use godot::{
+    classes::node::ProcessMode,
+    obj::{bounds::DeclUser, Bounds},
+    prelude::*,
+};
+
+pub trait Pooled {
+    // Puts the instance back in the state the scene was authored in.
+    fn reset(&mut self);
+    // Hands the instance back to its pool, usually through `park`.
+    fn release(&mut self);
+    fn released(&self) -> bool;
+}
+
+pub fn park(node: &mut Gd<Node2D>) {
+    node.hide();
+    node.set_process_mode(ProcessMode::DISABLED);
+}
+
+fn revive(node: &mut Gd<Node2D>) {
+    node.set_process_mode(ProcessMode::INHERIT);
+    node.show();
+}
+
+#[derive(Default, Clone, Copy)]
+struct PoolStats {
+    created: i64,
+    reused: i64,
+    peak: i64,
+}
+
+pub struct EffectPool<T: GodotClass> {
+    scene: Gd<PackedScene>,
+    instances: Vec<Gd<T>>,
+    stats: PoolStats,
+}
+
+impl<T> EffectPool<T>
+where
+    T: GodotClass + Bounds<Declarer = DeclUser> + Inherits<Node2D> + Pooled,
+{
+    pub fn new(scene: Gd<PackedScene>) -> Self {
+        Self {
+            scene,
+            instances: Vec::new(),
+            stats: PoolStats::default(),
+        }
+    }
+
+    pub fn prewarm(&mut self, parent: &mut Gd<Node>, count: usize) {
+        self.instances
+            .retain(|instance| instance.is_instance_valid());
+
+        while self.instances.len() < count {
+            let mut instance = self.create(parent);
+
+            instance.bind_mut().release();
+            self.instances.push(instance);
+        }
+    }
+
+    pub fn acquire(&mut self, parent: &mut Gd<Node>) -> Gd<T> {
+        self.instances
+            .retain(|instance| instance.is_instance_valid());
+
+        let mut instance = match self
+            .instances
+            .iter()
+            .find(|instance| instance.bind().released())
+        {
+            Some(instance) => {
+                self.stats.reused += 1;
+                instance.clone()
+            }
+            None => {
+                let instance = self.create(parent);
+
+                self.instances.push(instance.clone());
+                instance
+            }
+        };
+
+        instance.bind_mut().reset();
+        revive(&mut instance.clone().upcast());
+
+        self.stats.peak = self.stats.peak.max(self.active() as i64);
+
+        instance
+    }
+
+    fn create(&mut self, parent: &mut Gd<Node>) -> Gd<T> {
+        let instance = self.scene.instantiate().unwrap().cast::<T>();
+
+        self.stats.created += 1;
+        parent.add_child(&instance);
+
+        instance
+    }
+
+    fn active(&self) -> usize {
+        self.instances
+            .iter()
+            .filter(|instance| !instance.bind().released())
+            .count()
+    }
+
+    pub fn stats(&self) -> Dictionary {
+        let active = self.active() as i64;
+
+        dict! {
+            "created": self.stats.created,
+            "reused": self.stats.reused,
+            "active": active,
+            "available": self.instances.len() as i64 - active,
+            "peak": self.stats.peak,
+        }
+    }
+}
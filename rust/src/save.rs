@@ -0,0 +1,357 @@
+use crate::{enemy::Enemy, game::game_state, player::Player};
+use godot::{
+    classes::{
+        display_server::WindowMode, file_access::ModeFlags, notify::NodeNotification, DirAccess,
+        DisplayServer, FileAccess, Json,
+    },
+    prelude::*,
+};
+
+const SAVE_PATH: &str = "user://save.json";
+const SETTINGS_PATH: &str = "user://settings.json";
+const VOLUMES: [(&str, &str); 3] = [
+    ("Master", "master_volume"),
+    ("Music", "music_volume"),
+    ("SFX", "sfx_volume"),
+];
+
+// Each entry upgrades data of version `index + 1` to the next version.
+const MIGRATIONS: &[fn(&mut Dictionary)] = &[unlock_abilities];
+const SAVE_VERSION: i64 = MIGRATIONS.len() as i64 + 1;
+
+#[derive(Debug)]
+enum SaveError {
+    Missing,
+    Unreadable,
+    Corrupted,
+    Unsupported(i64),
+    Unwritable,
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+pub struct SaveManager {
+    #[export]
+    #[init(val = true)]
+    load_on_ready: bool,
+
+    // Volumes live on GameState, only the display setting is kept here.
+    #[var]
+    fullscreen: bool,
+
+    defeated: Vec<GString>,
+
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl SaveManager {
+    #[signal]
+    fn loaded();
+
+    #[func]
+    pub fn save_game(&mut self) -> bool {
+        let Some(tree) = self.base().get_tree() else {
+            return false;
+        };
+        let Some(scene) = tree.get_current_scene() else {
+            return false;
+        };
+
+        for enemy in tree.get_nodes_in_group("enemy").iter_shared() {
+            let Ok(enemy) = enemy.try_cast::<Enemy>() else {
+                continue;
+            };
+
+            let path = GString::from(scene.get_path_to(&enemy).to_string());
+
+            if enemy.bind().get_hp() <= 0. && !self.defeated.contains(&path) {
+                self.defeated.push(path);
+            }
+        }
+
+        let player = tree
+            .get_first_node_in_group("player")
+            .and_then(|player| player.try_cast::<Player>().ok())
+            .map(|player| player.bind().save())
+            .unwrap_or_default();
+
+        let data = dict! {
+            "room": scene.get_scene_file_path(),
+            "player": player,
+            "defeated": self.defeated.iter().cloned().collect::<Array<GString>>(),
+        };
+
+        report(write(SAVE_PATH, &data)).is_some()
+    }
+
+    #[func]
+    pub fn load_game(&mut self) -> bool {
+        let data = match read_file(SAVE_PATH) {
+            Err(SaveError::Missing) => return false,
+            data => data,
+        };
+
+        let Some(data) = report(data) else {
+            return false;
+        };
+
+        let Some(tree) = self.base().get_tree() else {
+            return false;
+        };
+        let Some(scene) = tree.get_current_scene() else {
+            return false;
+        };
+
+        let room = read::<GString>(&data, "room").unwrap_or_default();
+
+        if room != scene.get_scene_file_path() {
+            godot_warn!("Save belongs to {room}, skipping progress.");
+            return false;
+        }
+
+        self.defeated = read::<VariantArray>(&data, "defeated")
+            .map(|defeated| {
+                defeated
+                    .iter_shared()
+                    .filter_map(|path| path.try_to::<GString>().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for path in &self.defeated {
+            if let Some(mut enemy) = scene.try_get_node_as::<Enemy>(path.to_string().as_str()) {
+                enemy.queue_free();
+            }
+        }
+
+        if let (Some(player), Some(mut node)) = (
+            read::<Dictionary>(&data, "player"),
+            tree.get_first_node_in_group("player")
+                .and_then(|player| player.try_cast::<Player>().ok()),
+        ) {
+            node.bind_mut().restore(&player);
+        }
+
+        self.base_mut().emit_signal("loaded", &[]);
+
+        true
+    }
+
+    #[func]
+    pub fn erase_game(&mut self) {
+        self.defeated.clear();
+
+        for path in [SAVE_PATH.to_string(), backup(SAVE_PATH)] {
+            if FileAccess::file_exists(path.as_str()) {
+                DirAccess::remove_absolute(path.as_str());
+            }
+        }
+    }
+
+    #[func]
+    pub fn save_settings(&mut self) -> bool {
+        let mut data = dict! {
+            "fullscreen": self.fullscreen,
+        };
+
+        if let Some(state) = game_state(self.base().get_tree()) {
+            for (bus, key) in VOLUMES {
+                data.set(key, state.bind().volume(bus.into()));
+            }
+        }
+
+        self.apply_settings();
+
+        report(write(SETTINGS_PATH, &data)).is_some()
+    }
+
+    #[func]
+    pub fn load_settings(&mut self) -> bool {
+        let data = match read_file(SETTINGS_PATH) {
+            Err(SaveError::Missing) => return false,
+            data => data,
+        };
+
+        let Some(data) = report(data) else {
+            return false;
+        };
+
+        if let Some(mut state) = game_state(self.base().get_tree()) {
+            for (bus, key) in VOLUMES {
+                state
+                    .bind_mut()
+                    .set_volume(bus.into(), read(&data, key).unwrap_or(1.));
+            }
+        }
+
+        self.fullscreen = read(&data, "fullscreen").unwrap_or(false);
+
+        self.apply_settings();
+
+        true
+    }
+
+    #[func]
+    pub fn apply_settings(&mut self) {
+        DisplayServer::singleton().window_set_mode(if self.fullscreen {
+            WindowMode::FULLSCREEN
+        } else {
+            WindowMode::WINDOWED
+        });
+    }
+}
+
+#[godot_api]
+impl INode for SaveManager {
+    fn ready(&mut self) {
+        self.base_mut().add_to_group("save");
+
+        match game_state(self.base().get_tree()) {
+            Some(state) if state.bind().settings_loaded() => {
+                self.fullscreen =
+                    DisplayServer::singleton().window_get_mode() == WindowMode::FULLSCREEN;
+            }
+            Some(mut state) => {
+                self.load_settings();
+                state.bind_mut().mark_settings_loaded();
+            }
+            None => {
+                self.load_settings();
+            }
+        }
+
+        if self.load_on_ready {
+            self.base_mut().call_deferred("load_game", &[]);
+        }
+    }
+
+    fn on_notification(&mut self, what: NodeNotification) {
+        if what == NodeNotification::WM_CLOSE_REQUEST {
+            self.save_game();
+            self.save_settings();
+        }
+    }
+}
+
+pub fn read<T: FromGodot>(data: &Dictionary, key: &str) -> Option<T> {
+    data.get(key)?.try_to::<T>().ok()
+}
+
+pub fn read_vector(data: &Dictionary, key: &str) -> Option<Vector2> {
+    let value = read::<VariantArray>(data, key)?;
+
+    Some(Vector2::new(
+        value.get(0)?.try_to::<f32>().ok()?,
+        value.get(1)?.try_to::<f32>().ok()?,
+    ))
+}
+
+pub fn vector(value: Vector2) -> VariantArray {
+    varray![value.x, value.y]
+}
+
+fn backup(path: &str) -> String {
+    format!("{path}.bak")
+}
+
+fn checksum(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+fn write(path: &str, data: &Dictionary) -> Result<(), SaveError> {
+    let text = Json::stringify(&data.to_variant()).to_string();
+    let envelope = dict! {
+        "version": SAVE_VERSION,
+        "checksum": checksum(&text),
+        "data": text,
+    };
+
+    let temporary = format!("{path}.tmp");
+    let mut file =
+        FileAccess::open(temporary.as_str(), ModeFlags::WRITE).ok_or(SaveError::Unwritable)?;
+
+    file.store_string(&Json::stringify(&envelope.to_variant()));
+    file.close();
+
+    if FileAccess::file_exists(path) {
+        DirAccess::rename_absolute(path, backup(path).as_str());
+    }
+
+    if DirAccess::rename_absolute(temporary.as_str(), path) != godot::global::Error::OK {
+        return Err(SaveError::Unwritable);
+    }
+
+    Ok(())
+}
+
+fn read_file(path: &str) -> Result<Dictionary, SaveError> {
+    match parse(path) {
+        Err(SaveError::Missing) => Err(SaveError::Missing),
+        Err(error) => {
+            godot_warn!("{path} is unusable ({error:?}), trying backup.");
+            parse(backup(path).as_str()).map_err(|_| error)
+        }
+        data => data,
+    }
+}
+
+fn parse(path: &str) -> Result<Dictionary, SaveError> {
+    if !FileAccess::file_exists(path) {
+        return Err(SaveError::Missing);
+    }
+
+    let file = FileAccess::open(path, ModeFlags::READ).ok_or(SaveError::Unreadable)?;
+    let envelope = Json::parse_string(&file.get_as_text())
+        .try_to::<Dictionary>()
+        .map_err(|_| SaveError::Corrupted)?;
+
+    // JSON numbers always come back as floats.
+    let version = read::<f64>(&envelope, "version").ok_or(SaveError::Corrupted)? as i64;
+    let text = read::<GString>(&envelope, "data")
+        .ok_or(SaveError::Corrupted)?
+        .to_string();
+
+    if read::<GString>(&envelope, "checksum").map(|checksum| checksum.to_string())
+        != Some(checksum(&text))
+    {
+        return Err(SaveError::Corrupted);
+    }
+
+    if version < 1 || version > SAVE_VERSION {
+        return Err(SaveError::Unsupported(version));
+    }
+
+    let mut data = Json::parse_string(&text)
+        .try_to::<Dictionary>()
+        .map_err(|_| SaveError::Corrupted)?;
+
+    for migrate in &MIGRATIONS[(version - 1) as usize..] {
+        migrate(&mut data);
+    }
+
+    Ok(data)
+}
+
+// Saves from before the ability registry had every ability available.
+fn unlock_abilities(data: &mut Dictionary) {
+    if let Some(mut player) = read::<Dictionary>(data, "player") {
+        player.set(
+            "abilities",
+            dict! {
+                "locked": VariantArray::new(),
+                "tiers": Dictionary::new(),
+            },
+        );
+    }
+}
+
+fn report<T>(result: Result<T, SaveError>) -> Option<T> {
+    result
+        .map_err(|error| godot_error!("Save failed: {error:?}"))
+        .ok()
+}
@@ -0,0 +1,259 @@
+use godot::prelude::*;
+
+const TICK: f64 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StatusKind {
+    Burn,
+    Stun,
+    Slow,
+    Bleed,
+    Empower,
+    Weaken,
+}
+
+impl StatusKind {
+    fn max_stacks(self) -> u32 {
+        match self {
+            StatusKind::Bleed => 5,
+            _ => 1,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            StatusKind::Burn => Color::ORANGE,
+            StatusKind::Stun => Color::YELLOW,
+            StatusKind::Slow => Color::LIGHT_BLUE,
+            StatusKind::Bleed => Color::CRIMSON,
+            StatusKind::Empower => Color::GOLD,
+            StatusKind::Weaken => Color::MEDIUM_PURPLE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub duration: f64,
+    // Damage per second for each stack.
+    pub damage: f32,
+    pub speed_scale: f32,
+    // Scales the damage dealt by whoever carries the effect.
+    pub damage_scale: f32,
+}
+
+impl StatusEffect {
+    pub const fn burn(duration: f64, damage: f32) -> Self {
+        Self {
+            kind: StatusKind::Burn,
+            duration,
+            damage,
+            speed_scale: 1.,
+            damage_scale: 1.,
+        }
+    }
+
+    pub const fn stun(duration: f64) -> Self {
+        Self {
+            kind: StatusKind::Stun,
+            duration,
+            damage: 0.,
+            speed_scale: 0.,
+            damage_scale: 1.,
+        }
+    }
+
+    pub const fn slow(duration: f64, speed_scale: f32) -> Self {
+        Self {
+            kind: StatusKind::Slow,
+            duration,
+            damage: 0.,
+            speed_scale,
+            damage_scale: 1.,
+        }
+    }
+
+    pub const fn bleed(duration: f64, damage: f32) -> Self {
+        Self {
+            kind: StatusKind::Bleed,
+            duration,
+            damage,
+            speed_scale: 1.,
+            damage_scale: 1.,
+        }
+    }
+
+    pub const fn empower(duration: f64, damage_scale: f32) -> Self {
+        Self {
+            kind: StatusKind::Empower,
+            duration,
+            damage: 0.,
+            speed_scale: 1.,
+            damage_scale,
+        }
+    }
+
+    pub const fn weaken(duration: f64, damage_scale: f32) -> Self {
+        Self {
+            kind: StatusKind::Weaken,
+            duration,
+            damage: 0.,
+            speed_scale: 1.,
+            damage_scale,
+        }
+    }
+}
+
+struct Active {
+    effect: StatusEffect,
+    remaining: f64,
+    stacks: u32,
+    tick: f64,
+}
+
+#[derive(Default)]
+pub struct StatusEffects {
+    active: Vec<Active>,
+}
+
+impl StatusEffects {
+    // Reapplying refreshes the duration and keeps the stronger values. Only
+    // kinds with more than one stack add up their damage.
+    pub fn apply(&mut self, effect: StatusEffect) {
+        if let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.effect.kind == effect.kind)
+        {
+            active.remaining = active.remaining.max(effect.duration);
+            active.stacks = (active.stacks + 1).min(effect.kind.max_stacks());
+            active.effect.damage = active.effect.damage.max(effect.damage);
+            active.effect.speed_scale = active.effect.speed_scale.min(effect.speed_scale);
+            // Buffs keep the higher scale, debuffs the lower one.
+            active.effect.damage_scale = if effect.damage_scale > 1. {
+                active.effect.damage_scale.max(effect.damage_scale)
+            } else {
+                active.effect.damage_scale.min(effect.damage_scale)
+            };
+
+            return;
+        }
+
+        self.active.push(Active {
+            effect,
+            remaining: effect.duration,
+            stacks: 1,
+            tick: 0.,
+        });
+    }
+
+    pub fn apply_all(&mut self, effects: &[StatusEffect]) {
+        for effect in effects {
+            self.apply(*effect);
+        }
+    }
+
+    // Returns the periodic damage dealt during this step.
+    pub fn update(&mut self, delta: f64) -> f32 {
+        let mut damage = 0.;
+
+        for active in &mut self.active {
+            active.remaining -= delta;
+            active.tick += delta;
+
+            while active.tick >= TICK {
+                active.tick -= TICK;
+                damage += active.effect.damage * active.stacks as f32 * TICK as f32;
+            }
+        }
+
+        self.active.retain(|active| active.remaining > 0.);
+
+        damage
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn stunned(&self) -> bool {
+        self.active
+            .iter()
+            .any(|active| active.effect.kind == StatusKind::Stun)
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        self.active
+            .iter()
+            .map(|active| active.effect.speed_scale)
+            .product()
+    }
+
+    pub fn damage_scale(&self) -> f32 {
+        self.active
+            .iter()
+            .map(|active| active.effect.damage_scale)
+            .product()
+    }
+
+    pub fn tint(&self) -> Color {
+        self.active
+            .last()
+            .map(|active| Color::WHITE.lerp(active.effect.kind.color(), 0.5))
+            .unwrap_or(Color::WHITE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empower_raises_damage() {
+        let mut statuses = StatusEffects::default();
+
+        statuses.apply(StatusEffect::empower(2., 1.25));
+
+        assert_eq!(statuses.damage_scale(), 1.25);
+        assert_eq!(statuses.tint(), Color::WHITE.lerp(Color::GOLD, 0.5));
+    }
+
+    #[test]
+    fn empower_keeps_the_stronger_buff() {
+        let mut statuses = StatusEffects::default();
+
+        statuses.apply(StatusEffect::empower(1., 1.5));
+        statuses.apply(StatusEffect::empower(3., 1.25));
+
+        assert_eq!(statuses.damage_scale(), 1.5);
+
+        statuses.update(2.);
+
+        assert_eq!(statuses.damage_scale(), 1.5);
+
+        statuses.update(1.);
+
+        assert_eq!(statuses.damage_scale(), 1.);
+    }
+
+    #[test]
+    fn weaken_keeps_the_stronger_debuff() {
+        let mut statuses = StatusEffects::default();
+
+        statuses.apply(StatusEffect::weaken(1., 0.5));
+        statuses.apply(StatusEffect::weaken(1., 0.75));
+
+        assert_eq!(statuses.damage_scale(), 0.5);
+    }
+
+    #[test]
+    fn buffs_and_debuffs_combine() {
+        let mut statuses = StatusEffects::default();
+
+        statuses.apply(StatusEffect::empower(1., 2.));
+        statuses.apply(StatusEffect::weaken(1., 0.75));
+
+        assert_eq!(statuses.damage_scale(), 1.5);
+    }
+}
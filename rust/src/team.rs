@@ -0,0 +1,21 @@
+use godot::prelude::*;
+
+#[derive(GodotConvert, Var, Export, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[godot(via = i64)]
+pub enum Team {
+    // Left out of combat: it neither deals nor takes damage, whatever the
+    // other side or friendly fire.
+    #[default]
+    Neutral,
+    Player,
+    Enemy,
+}
+
+// Different teams always hurt each other, the same team only with friendly fire.
+pub fn hostile(attacker: Team, target: Team, friendly_fire: bool) -> bool {
+    if attacker == Team::Neutral || target == Team::Neutral {
+        return false;
+    }
+
+    attacker != target || friendly_fire
+}